@@ -1,22 +1,15 @@
 #[macro_use]
 extern crate rocket;
 
-use std::{io::ErrorKind, path::Path};
+use std::path::Path;
 
-use rocket::http::Status;
-use rocket_raw_response::RawResponse;
+use rocket_raw_response::{RawResponse, RawResponseError};
 
 #[get("/")]
-async fn view() -> Result<RawResponse, Status> {
+async fn view() -> Result<RawResponse, RawResponseError> {
     let path = Path::join(Path::new("examples"), Path::join(Path::new("images"), "image(貓).jpg"));
 
-    RawResponse::from_file(path, None::<String>, None).await.map_err(|err| {
-        if err.kind() == ErrorKind::NotFound {
-            Status::NotFound
-        } else {
-            Status::InternalServerError
-        }
-    })
+    RawResponse::file(path).build().await
 }
 
 #[launch]
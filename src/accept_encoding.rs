@@ -0,0 +1,142 @@
+use rocket::request::Request;
+
+/// Choose a content coding from the `Accept-Encoding` header of a request. `codings` are in the order of preference of the server. Returns `None` if the identity should be used.
+pub(crate) fn preferred<'a>(request: &Request<'_>, codings: &[&'a str]) -> Option<&'a str> {
+    let mut accepted: Vec<(&str, f32)> = Vec::new();
+
+    for value in request.headers().get("Accept-Encoding") {
+        for item in value.split(',') {
+            let mut parts = item.split(';');
+
+            let coding = parts.next().unwrap_or("").trim();
+
+            if coding.is_empty() {
+                continue;
+            }
+
+            let mut q = Some(1.0);
+
+            for param in parts {
+                if let Some((name, value)) = param.split_once('=') {
+                    if name.trim().eq_ignore_ascii_case("q") {
+                        q = value.trim().parse::<f32>().ok().filter(|q| (0.0..=1.0).contains(q));
+                    }
+                }
+            }
+
+            if let Some(q) = q {
+                accepted.push((coding, q));
+            }
+        }
+    }
+
+    let q_of = |matches: &dyn Fn(&str) -> bool| {
+        accepted.iter().find(|(coding, _)| matches(coding)).map(|(_, q)| *q)
+    };
+
+    let star = q_of(&|coding| coding == "*");
+    let identity = q_of(&|coding| coding.eq_ignore_ascii_case("identity"));
+
+    let mut best: Option<(&str, f32)> = None;
+
+    for &candidate in codings {
+        let q = q_of(&|coding| is_same_coding(candidate, coding)).or(star).unwrap_or(0.0);
+
+        if q > 0.0 && best.map_or(true, |(_, best_q)| q > best_q) {
+            best = Some((candidate, q));
+        }
+    }
+
+    match (best, identity) {
+        (Some((_, q)), Some(identity_q)) if identity_q > q => None,
+        (best, _) => best.map(|(coding, _)| coding),
+    }
+}
+
+/// Compare content codings case-insensitively, treating `x-gzip` as `gzip`.
+#[inline]
+fn is_same_coding(a: &str, b: &str) -> bool {
+    let normalize = |coding: &str| {
+        if coding.eq_ignore_ascii_case("x-gzip") {
+            "gzip".to_string()
+        } else {
+            coding.to_ascii_lowercase()
+        }
+    };
+
+    normalize(a) == normalize(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::{http::Header, local::blocking::Client};
+
+    use super::*;
+    use crate::testing::blocking_client;
+
+    const CODINGS: [&str; 3] = ["br", "zstd", "gzip"];
+
+    fn preferred_for(
+        client: &Client,
+        accept_encoding: Option<&'static str>,
+    ) -> Option<&'static str> {
+        let mut request = client.get("/");
+
+        if let Some(accept_encoding) = accept_encoding {
+            request = request.header(Header::new("Accept-Encoding", accept_encoding));
+        }
+
+        preferred(request.inner(), &CODINGS)
+    }
+
+    #[test]
+    fn preferred_without_header() {
+        let client = blocking_client();
+
+        assert_eq!(None, preferred_for(&client, None));
+        assert_eq!(None, preferred_for(&client, Some("")));
+    }
+
+    #[test]
+    fn preferred_by_server_order() {
+        let client = blocking_client();
+
+        assert_eq!(Some("br"), preferred_for(&client, Some("gzip, deflate, br")));
+        assert_eq!(Some("gzip"), preferred_for(&client, Some("GZIP")));
+        assert_eq!(Some("gzip"), preferred_for(&client, Some("x-gzip")));
+        assert_eq!(None, preferred_for(&client, Some("deflate")));
+    }
+
+    #[test]
+    fn preferred_by_q_values() {
+        let client = blocking_client();
+
+        assert_eq!(Some("gzip"), preferred_for(&client, Some("br;q=0.5, gzip")));
+        assert_eq!(Some("zstd"), preferred_for(&client, Some("br;q=0.5, zstd;q=0.8, gzip;q=0.1")));
+        assert_eq!(Some("gzip"), preferred_for(&client, Some("br;q=0, gzip;q=0.1")));
+        assert_eq!(Some("gzip"), preferred_for(&client, Some("br; q=2, gzip")));
+        assert_eq!(Some("gzip"), preferred_for(&client, Some("br;q=x, gzip")));
+        assert_eq!(None, preferred_for(&client, Some("gzip;q=0")));
+    }
+
+    #[test]
+    fn preferred_with_wildcard() {
+        let client = blocking_client();
+
+        assert_eq!(Some("br"), preferred_for(&client, Some("*")));
+        assert_eq!(Some("zstd"), preferred_for(&client, Some("br;q=0, *")));
+        assert_eq!(Some("gzip"), preferred_for(&client, Some("gzip, *;q=0")));
+        assert_eq!(None, preferred_for(&client, Some("*;q=0")));
+    }
+
+    #[test]
+    fn preferred_against_identity() {
+        let client = blocking_client();
+
+        assert_eq!(None, preferred_for(&client, Some("gzip;q=0.5, identity")));
+        assert_eq!(Some("gzip"), preferred_for(&client, Some("gzip;q=0.5, identity;q=0.5")));
+        assert_eq!(Some("gzip"), preferred_for(&client, Some("gzip, identity;q=0")));
+        assert_eq!(Some("br"), preferred_for(&client, Some("*, identity;q=0")));
+        assert_eq!(None, preferred_for(&client, Some("deflate, identity;q=0")));
+    }
+}
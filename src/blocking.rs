@@ -0,0 +1,56 @@
+use std::{
+    fs::{File, Metadata},
+    io,
+    path::Path,
+};
+
+use rocket::tokio::{
+    runtime::{Handle, RuntimeFlavor},
+    task,
+};
+
+use crate::RawResponseError;
+
+/// Run blocking I/O from `respond_to`, which cannot await. On a multi-threaded runtime, the other tasks of the worker are moved to another thread meanwhile. On a current-thread runtime, e.g. the one of a blocking local client, it just blocks.
+pub(crate) fn block_in_place<F: FnOnce() -> R, R>(f: F) -> R {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            task::block_in_place(f)
+        },
+        _ => f(),
+    }
+}
+
+/// Run blocking I/O from an async builder on the blocking pool, so that it shares its implementation with `block_in_place`.
+pub(crate) async fn spawn<F, R>(f: F) -> Result<R, RawResponseError>
+where
+    F: FnOnce() -> Result<R, RawResponseError> + Send + 'static,
+    R: Send + 'static, {
+    task::spawn_blocking(f)
+        .await
+        .map_err(|error| RawResponseError::Io(io::Error::new(io::ErrorKind::Other, error)))?
+}
+
+/// Open a file and read its metadata from the opened handle, so that both describe the same file.
+#[inline]
+pub(crate) fn open_file(path: &Path) -> Result<(File, Metadata), io::Error> {
+    let file = File::open(path)?;
+
+    let metadata = file.metadata()?;
+
+    Ok((file, metadata))
+}
+
+/// Open an uploaded file stored on disk. A persisted file which is not there anymore has been moved or deleted on purpose, so it is `RawResponseError::Gone`.
+pub(crate) fn open_temp_file(
+    path: &Path,
+    persisted: bool,
+) -> Result<(File, Metadata), RawResponseError> {
+    open_file(path).map_err(|error| {
+        if error.kind() == io::ErrorKind::NotFound && persisted {
+            RawResponseError::Gone
+        } else {
+            RawResponseError::from(error)
+        }
+    })
+}
@@ -0,0 +1,656 @@
+/*!
+A builder for `RawResponsePro`, created by `RawResponsePro::slice`, `RawResponsePro::vec`, `RawResponsePro::bytes`, `RawResponsePro::arc`, `RawResponsePro::cow`, `RawResponsePro::reader`, `RawResponsePro::stream`, `RawResponsePro::std_reader`, `RawResponsePro::writer`, `RawResponsePro::command`, `RawResponsePro::file`, `RawResponsePro::lazy_file` or `RawResponsePro::temp_file`.
+
+A body which fails while it is sent, e.g. a stream, a reader or a command returning an error, aborts the connection instead of ending the body early.
+
+```rust,no_run
+use rocket_raw_response::{mime, CacheControl, Disposition, RawResponse};
+
+# async fn f() -> Result<RawResponse, rocket_raw_response::RawResponseError> {
+let response = RawResponse::file(std::path::Path::new("report.pdf"))
+    .file_name("月報.pdf")
+    .content_type(mime::APPLICATION_PDF)
+    .disposition(Disposition::Attachment)
+    .cache_control(CacheControl::REVALIDATE)
+    .build()
+    .await?;
+# Ok(response)
+# }
+```
+*/
+
+use std::{
+    borrow::Cow,
+    future::Future,
+    io::{self, Cursor, SeekFrom},
+    marker::{PhantomData, Unpin},
+    path::Path,
+    process::Stdio,
+    sync::Arc,
+    time::SystemTime,
+};
+
+use bytes::Bytes;
+use mime::Mime;
+use rocket::{
+    fs::TempFile,
+    futures::Stream,
+    tokio::{
+        fs::File as AsyncFile,
+        io::{AsyncRead, AsyncReadExt, AsyncSeekExt, DuplexStream},
+        process::Command,
+    },
+};
+
+use crate::{
+    blocking, charset, command_async_reader, precompressed, sniff, std_reader,
+    stream_async_reader::StreamAsyncReader, writer_async_reader::WriterAsyncReader, CacheControl,
+    ContentTypePolicy, DefaultMimeResolver, Disposition, EntityTag, MimeResolver, RawResponseData,
+    RawResponseError, RawResponsePro, SafetyPolicy, DEFAULT_MAX_RANGES,
+};
+
+/// The options of a `RawResponsePro` which do not depend on where the data comes from.
+#[derive(Educe)]
+#[educe(Debug)]
+pub(crate) struct RawResponseOptions {
+    pub(crate) file_name:            Option<String>,
+    pub(crate) disposition:          Disposition,
+    pub(crate) content_type:         Option<Mime>,
+    pub(crate) max_ranges:           usize,
+    pub(crate) etag:                 Option<EntityTag>,
+    pub(crate) last_modified:        Option<SystemTime>,
+    pub(crate) cache_control:        Option<CacheControl>,
+    pub(crate) sniff:                bool,
+    /// The content type guessed from the leading bytes, which is used when no other content type is known.
+    pub(crate) sniffed_content_type: Option<Mime>,
+    /// The charset detected from the leading bytes, which is appended to an inferred textual content type.
+    pub(crate) charset:              Option<&'static str>,
+    pub(crate) safety_policy:        Option<SafetyPolicy>,
+    pub(crate) content_type_policy:  Option<ContentTypePolicy>,
+    #[educe(Debug(ignore))]
+    pub(crate) mime_resolver:        Option<Arc<dyn MimeResolver>>,
+    #[cfg(feature = "compression")]
+    pub(crate) compress:             bool,
+}
+
+impl Default for RawResponseOptions {
+    #[inline]
+    fn default() -> Self {
+        RawResponseOptions {
+            file_name:                                None,
+            disposition:                              Disposition::default(),
+            content_type:                             None,
+            max_ranges:                               DEFAULT_MAX_RANGES,
+            etag:                                     None,
+            last_modified:                            None,
+            cache_control:                            None,
+            sniff:                                    false,
+            sniffed_content_type:                     None,
+            charset:                                  None,
+            safety_policy:                            None,
+            content_type_policy:                      None,
+            mime_resolver:                            None,
+            #[cfg(feature = "compression")]
+            compress:                                 false,
+        }
+    }
+}
+
+impl RawResponseOptions {
+    /// The number of leading bytes `inspect` needs.
+    #[inline]
+    fn inspect_len(&self) -> usize {
+        if self.sniff {
+            sniff::SNIFF_LEN
+        } else {
+            charset::BOM_LEN
+        }
+    }
+
+    /// Whether the leading bytes of a file need to be inspected, i.e. the content type is not set, and sniffing is enabled or the content type guessed from the file name may be textual.
+    ///
+    /// The resolver of the response comes first, then `managed`, the resolver of a `MimeResolverState`, which is only known when the response is responded, and then the `DefaultMimeResolver`.
+    fn needs_inspect(&self, path: &Path, managed: Option<&dyn MimeResolver>) -> bool {
+        if self.content_type.is_some() {
+            return false;
+        }
+
+        if self.sniff {
+            return true;
+        }
+
+        let mime_resolver: &dyn MimeResolver = match self.mime_resolver.as_deref().or(managed) {
+            Some(mime_resolver) => mime_resolver,
+            None => &DefaultMimeResolver,
+        };
+
+        match path.file_name().and_then(|file_name| file_name.to_str()) {
+            Some(file_name) => match mime_resolver.resolve(file_name) {
+                Some(content_type) => charset::is_textual(&content_type),
+                None => true,
+            },
+            None => true,
+        }
+    }
+
+    /// Inspect the leading bytes of the data to detect its charset and, if sniffing is enabled, its content type.
+    #[inline]
+    fn inspect(&mut self, bytes: &[u8]) {
+        self.charset = Some(charset::from_bom(bytes));
+
+        if self.sniff {
+            self.sniffed_content_type = sniff::sniff(bytes);
+        }
+    }
+}
+
+/// Data which is ready to be responded, i.e. a slice, a vector, a shared buffer or a `TempFile`.
+#[derive(Debug)]
+pub struct DataSource<'o> {
+    data: RawResponseData<'o>,
+}
+
+/// A reader and its optional length.
+#[derive(Educe)]
+#[educe(Debug)]
+pub struct ReaderSource<'o> {
+    #[educe(Debug(ignore))]
+    reader:         Box<dyn AsyncRead + Send + Unpin + 'o>,
+    content_length: Option<u64>,
+}
+
+/// A command which is going to be spawned by `RawResponseBuilder::build`.
+#[derive(Debug)]
+pub struct CommandSource {
+    command: Command,
+    stdin:   Option<Stdio>,
+}
+
+/// A path of a file which is going to be opened by `RawResponseBuilder::build`.
+#[derive(Debug)]
+pub struct FileSource {
+    path:          Arc<Path>,
+    precompressed: bool,
+}
+
+/// A path of a file which is going to be opened when the response is responded.
+#[derive(Debug)]
+pub struct LazyFileSource {
+    path:          Arc<Path>,
+    precompressed: bool,
+}
+
+/// A builder for `RawResponsePro`.
+#[derive(Debug)]
+pub struct RawResponseBuilder<'o, S> {
+    source:  S,
+    options: RawResponseOptions,
+    _marker: PhantomData<&'o ()>,
+}
+
+impl<'o, S> RawResponseBuilder<'o, S> {
+    #[inline]
+    fn new(source: S) -> Self {
+        RawResponseBuilder {
+            source,
+            options: RawResponseOptions::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Set the file name used in the `Content-Disposition` header.
+    ///
+    /// If it is not set, the name of the file, or the name of the uploaded temporary file, is used. An empty file name means no file name at all.
+    #[inline]
+    pub fn file_name<N: Into<String>>(mut self, file_name: N) -> Self {
+        self.options.file_name = Some(file_name.into());
+
+        self
+    }
+
+    /// Set the disposition type of the `Content-Disposition` header. The default value is `Disposition::Inline`.
+    #[inline]
+    pub fn disposition(mut self, disposition: Disposition) -> Self {
+        self.options.disposition = disposition;
+
+        self
+    }
+
+    /// Set the content type. If it is not set, the type is guessed from the extension of the file name.
+    #[inline]
+    pub fn content_type(mut self, content_type: Mime) -> Self {
+        self.options.content_type = Some(content_type);
+
+        self
+    }
+
+    /// Set the maximum number of ranges a request can ask for. See `RawResponsePro::set_max_ranges`.
+    #[inline]
+    pub fn max_ranges(mut self, max_ranges: usize) -> Self {
+        self.options.max_ranges = max_ranges;
+
+        self
+    }
+
+    /// Set the entity tag, replacing the generated one. See `RawResponsePro::set_etag`.
+    #[inline]
+    pub fn etag(mut self, etag: EntityTag) -> Self {
+        self.options.etag = Some(etag);
+
+        self
+    }
+
+    /// Set the time the content was last modified, replacing the modification time of a file.
+    #[inline]
+    pub fn last_modified(mut self, last_modified: SystemTime) -> Self {
+        self.options.last_modified = Some(last_modified);
+
+        self
+    }
+
+    /// Set the caching policy, which is sent as the `Cache-Control` and `Expires` headers.
+    #[inline]
+    pub fn cache_control(mut self, cache_control: CacheControl) -> Self {
+        self.options.cache_control = Some(cache_control);
+
+        self
+    }
+
+    /// Set whether the content type is guessed from the leading bytes of the data when it is not set and cannot be guessed from the file name. The default value is `false`.
+    ///
+    /// A reader and an uploaded file stored on disk are only sniffed by `RawResponseBuilder::sniff_content_type`, because their bytes have to be read first, and calling it without enabling this only detects the charset.
+    #[inline]
+    pub fn sniff(mut self, sniff: bool) -> Self {
+        self.options.sniff = sniff;
+
+        self
+    }
+
+    /// Set the policy for serving untrusted content, such as user uploads. See `SafetyPolicy`.
+    #[inline]
+    pub fn safety_policy(mut self, safety_policy: SafetyPolicy) -> Self {
+        self.options.safety_policy = Some(safety_policy);
+
+        self
+    }
+
+    /// Set the policy deciding which content types may be responded. See `RawResponsePro::set_content_type_policy`.
+    #[inline]
+    pub fn content_type_policy(mut self, content_type_policy: ContentTypePolicy) -> Self {
+        self.options.content_type_policy = Some(content_type_policy);
+
+        self
+    }
+
+    /// Set the `MimeResolver` used to guess the content type from the file name. See `RawResponsePro::set_mime_resolver`.
+    #[inline]
+    pub fn mime_resolver<R: MimeResolver + 'static>(mut self, mime_resolver: R) -> Self {
+        self.options.mime_resolver = Some(Arc::new(mime_resolver));
+
+        self
+    }
+
+    /// Set whether the body is compressed. See `RawResponsePro::set_compress`.
+    #[cfg(feature = "compression")]
+    #[inline]
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.options.compress = compress;
+
+        self
+    }
+
+    /// Set the file name and the content type the way the `from_*` constructors take them.
+    #[inline]
+    pub(crate) fn optional<N: Into<String>>(
+        mut self,
+        file_name: Option<N>,
+        content_type: Option<Mime>,
+    ) -> Self {
+        self.options.file_name = file_name.map(|file_name| file_name.into());
+        self.options.content_type = content_type;
+
+        self
+    }
+}
+
+impl<'o> RawResponseBuilder<'o, DataSource<'o>> {
+    /// Open an uploaded file stored on disk and read its metadata on the blocking pool, so that a missing file is reported before the response is responded. Data in memory is not affected.
+    pub async fn open(mut self) -> Result<Self, RawResponseError> {
+        if let RawResponseData::TempFile(file, opened @ None) = &mut self.source.data {
+            if let TempFile::File {
+                path, ..
+            } = file.as_ref()
+            {
+                let persisted = path.is_right();
+                let path = AsRef::<Path>::as_ref(path).to_path_buf();
+
+                let (file, metadata) =
+                    blocking::spawn(move || blocking::open_temp_file(&path, persisted)).await?;
+
+                *opened = Some((AsyncFile::from_std(file), Box::new(metadata)));
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Read the leading bytes of an uploaded file stored on disk to detect the charset and, if `RawResponseBuilder::sniff` is enabled, guess the content type, if the content type is not set. The file is opened by `RawResponseBuilder::open` first. Data in memory is inspected by `RawResponseBuilder::build` without this.
+    pub async fn sniff_content_type(self) -> Result<Self, RawResponseError> {
+        if self.options.content_type.is_some() {
+            return Ok(self);
+        }
+
+        let mut builder = self.open().await?;
+
+        if let RawResponseData::TempFile(_, Some((file, _))) = &mut builder.source.data {
+            let len = builder.options.inspect_len();
+            let mut buffer = Vec::with_capacity(len);
+
+            file.take(len as u64).read_to_end(&mut buffer).await?;
+            file.seek(SeekFrom::Start(0)).await?;
+
+            builder.options.inspect(&buffer);
+        }
+
+        Ok(builder)
+    }
+
+    /// Create the `RawResponsePro` instance. The leading bytes of data in memory are inspected, but no I/O is done, so an uploaded file stored on disk is only inspected by `RawResponseBuilder::sniff_content_type`, and it is opened when the response is responded unless `RawResponseBuilder::open` has opened it.
+    pub fn build(mut self) -> RawResponsePro<'o> {
+        if self.options.content_type.is_none() {
+            match &self.source.data {
+                RawResponseData::Slice(data) => self.options.inspect(data),
+                RawResponseData::Vec(data) => self.options.inspect(data),
+                RawResponseData::Bytes(data) => self.options.inspect(data),
+                RawResponseData::Shared(data) => self.options.inspect(data),
+                RawResponseData::TempFile(file, _) => match file.as_ref() {
+                    TempFile::Buffered {
+                        content,
+                    } => self.options.inspect(content),
+                    TempFile::File {
+                        ..
+                    } => (),
+                },
+                _ => (),
+            }
+        }
+
+        RawResponsePro {
+            options: self.options, data: self.source.data
+        }
+    }
+}
+
+impl<'o> RawResponseBuilder<'o, ReaderSource<'o>> {
+    /// Set the length of the data the reader is going to produce, which is sent as the `Content-Length` header.
+    #[inline]
+    pub fn content_length(mut self, content_length: u64) -> Self {
+        self.source.content_length = Some(content_length);
+
+        self
+    }
+
+    /// Read the leading bytes of the reader to detect the charset and, if `RawResponseBuilder::sniff` is enabled, guess the content type, if the content type is not set. The bytes which are read are still responded.
+    pub async fn sniff_content_type(mut self) -> Result<Self, RawResponseError> {
+        if self.options.content_type.is_some() {
+            return Ok(self);
+        }
+
+        let len = self.options.inspect_len();
+        let mut buffer = Vec::with_capacity(len);
+
+        (&mut self.source.reader).take(len as u64).read_to_end(&mut buffer).await?;
+
+        self.options.inspect(&buffer);
+        self.source.reader = Box::new(Cursor::new(buffer).chain(self.source.reader));
+
+        Ok(self)
+    }
+
+    /// Create the `RawResponsePro` instance.
+    #[inline]
+    pub fn build(self) -> RawResponsePro<'o> {
+        let data = RawResponseData::Reader {
+            data:           self.source.reader,
+            content_length: self.source.content_length,
+        };
+
+        RawResponsePro {
+            options: self.options,
+            data,
+        }
+    }
+}
+
+impl<'o> RawResponseBuilder<'o, CommandSource> {
+    /// Set the standard input of the command. The default value is `Stdio::null()`, so that a command never reads the standard input of the server, e.g. `ffmpeg` waiting for interactive commands.
+    ///
+    /// A standard input set on the `Command` itself is overridden, so it has to be set here.
+    #[inline]
+    pub fn stdin<T: Into<Stdio>>(mut self, stdin: T) -> Self {
+        self.source.stdin = Some(stdin.into());
+
+        self
+    }
+
+    /// Spawn the command, wait for its first output and create the `RawResponsePro` instance. The standard output of the command is piped, and the command is killed when the response is dropped.
+    pub async fn build(self) -> Result<RawResponsePro<'o>, RawResponseError> {
+        let mut command = self.source.command;
+
+        command.stdin(self.source.stdin.unwrap_or_else(Stdio::null));
+
+        let reader = command_async_reader::spawn(command).await?;
+
+        Ok(RawResponsePro {
+            options: self.options,
+            data:    RawResponseData::Reader {
+                data:           Box::new(reader),
+                content_length: None,
+            },
+        })
+    }
+}
+
+impl<'o> RawResponseBuilder<'o, FileSource> {
+    /// Set whether precompressed sidecar files, i.e. `.br`, `.zst` and `.gz` files next to the file, are served when the `Accept-Encoding` header of the request allows them. The default value is `false`.
+    ///
+    /// The `Content-Type` header and the file name of the `Content-Disposition` header still come from the original path. A sidecar file older than the original file is ignored.
+    #[inline]
+    pub fn precompressed(mut self, precompressed: bool) -> Self {
+        self.source.precompressed = precompressed;
+
+        self
+    }
+
+    /// Open the file and create the `RawResponsePro` instance. A missing file, a file which cannot be accessed and a directory are distinguished by `RawResponseError`.
+    ///
+    /// If the content type is not set and may be textual, or sniffing is enabled, the leading bytes of the file are read too, to detect the charset. The file system is accessed on the blocking pool.
+    pub async fn build(self) -> Result<RawResponsePro<'o>, RawResponseError> {
+        let FileSource {
+            path,
+            precompressed,
+        } = self.source;
+
+        let mut options = self.options;
+
+        let (options, data) = blocking::spawn(move || {
+            let data = open_file(path, precompressed, &mut options, None)?;
+
+            Ok((options, data))
+        })
+        .await?;
+
+        Ok(RawResponsePro {
+            options,
+            data,
+        })
+    }
+}
+
+impl<'o> RawResponseBuilder<'o, LazyFileSource> {
+    /// Set whether precompressed sidecar files are served, the same as the `precompressed` method of a `RawResponsePro::file` builder.
+    #[inline]
+    pub fn precompressed(mut self, precompressed: bool) -> Self {
+        self.source.precompressed = precompressed;
+
+        self
+    }
+
+    /// Create the `RawResponsePro` instance without touching the file. It is opened when the response is responded, and a failure is responded as a `RawResponseError`.
+    #[inline]
+    pub fn build(self) -> RawResponsePro<'o> {
+        RawResponsePro {
+            options: self.options,
+            data:    RawResponseData::LazyFile(self.source.path, self.source.precompressed),
+        }
+    }
+}
+
+/// Open a file, inspect its leading bytes if needed and open its sidecar files, with blocking I/O. `RawResponsePro::file` runs it on the blocking pool and `RawResponsePro::lazy_file` in `blocking::block_in_place`, with the resolver of a `MimeResolverState` as `managed`.
+pub(crate) fn open_file(
+    path: Arc<Path>,
+    precompressed: bool,
+    options: &mut RawResponseOptions,
+    managed: Option<&dyn MimeResolver>,
+) -> Result<RawResponseData<'static>, RawResponseError> {
+    use std::io::{Read, Seek};
+
+    let (mut file, metadata) = blocking::open_file(&path)?;
+
+    if metadata.is_dir() {
+        return Err(RawResponseError::IsADirectory);
+    }
+
+    if options.needs_inspect(&path, managed) {
+        let len = options.inspect_len();
+        let mut buffer = Vec::with_capacity(len);
+
+        (&mut file).take(len as u64).read_to_end(&mut buffer)?;
+        file.seek(SeekFrom::Start(0))?;
+
+        options.inspect(&buffer);
+    }
+
+    let sidecars =
+        if precompressed { Some(precompressed::open_sidecars(&path, &metadata)) } else { None };
+
+    Ok(RawResponseData::File(path, AsyncFile::from_std(file), Box::new(metadata), sidecars))
+}
+
+impl<'o> RawResponsePro<'o> {
+    /// Start building a `RawResponse` instance from a `&'o [u8]`.
+    #[inline]
+    pub fn slice(data: &'o [u8]) -> RawResponseBuilder<'o, DataSource<'o>> {
+        RawResponseBuilder::new(DataSource {
+            data: RawResponseData::Slice(data)
+        })
+    }
+
+    /// Start building a `RawResponse` instance from a `Vec<u8>`.
+    #[inline]
+    pub fn vec(vec: Vec<u8>) -> RawResponseBuilder<'o, DataSource<'o>> {
+        RawResponseBuilder::new(DataSource {
+            data: RawResponseData::Vec(vec)
+        })
+    }
+
+    /// Start building a `RawResponse` instance from a `Bytes`.
+    #[inline]
+    pub fn bytes(bytes: Bytes) -> RawResponseBuilder<'o, DataSource<'o>> {
+        RawResponseBuilder::new(DataSource {
+            data: RawResponseData::Bytes(bytes)
+        })
+    }
+
+    /// Start building a `RawResponse` instance from an `Arc<[u8]>`.
+    #[inline]
+    pub fn arc(data: Arc<[u8]>) -> RawResponseBuilder<'o, DataSource<'o>> {
+        RawResponseBuilder::new(DataSource {
+            data: RawResponseData::Shared(data)
+        })
+    }
+
+    /// Start building a `RawResponse` instance from a `Cow<'static, [u8]>`. Borrowed data is responded without copying, like a slice.
+    #[inline]
+    pub fn cow(data: Cow<'static, [u8]>) -> RawResponseBuilder<'o, DataSource<'o>> {
+        RawResponseBuilder::new(DataSource {
+            data: match data {
+                Cow::Borrowed(data) => RawResponseData::Slice(data),
+                Cow::Owned(data) => RawResponseData::Vec(data),
+            },
+        })
+    }
+
+    /// Start building a `RawResponse` instance from a reader.
+    #[inline]
+    pub fn reader<R: AsyncRead + Send + Unpin + 'o>(
+        reader: R,
+    ) -> RawResponseBuilder<'o, ReaderSource<'o>> {
+        RawResponseBuilder::new(ReaderSource {
+            reader:         Box::new(reader),
+            content_length: None,
+        })
+    }
+
+    /// Start building a `RawResponse` instance from a stream of chunks.
+    #[inline]
+    pub fn stream<S: Stream<Item = Result<Bytes, io::Error>> + Send + 'o>(
+        stream: S,
+    ) -> RawResponseBuilder<'o, ReaderSource<'o>> {
+        RawResponsePro::reader(StreamAsyncReader::new(stream))
+    }
+
+    /// Start building a `RawResponse` instance from a blocking reader, which is read in chunks on the blocking pool. A panic of the reader is an error too.
+    #[inline]
+    pub fn std_reader<R: std::io::Read + Send + 'static>(
+        reader: R,
+    ) -> RawResponseBuilder<'o, ReaderSource<'o>> {
+        RawResponsePro::stream(std_reader::chunks(reader))
+    }
+
+    /// Start building a `RawResponse` instance from a producer writing the body to a pipe. The producer is spawned when the body is read and aborted when the client disconnects.
+    #[inline]
+    pub fn writer<F, Fut>(producer: F) -> RawResponseBuilder<'o, ReaderSource<'o>>
+    where
+        F: FnOnce(DuplexStream) -> Fut + Send + 'static,
+        Fut: Future<Output = io::Result<()>> + Send + 'static, {
+        RawResponsePro::reader(WriterAsyncReader::new(producer))
+    }
+
+    /// Start building a `RawResponse` instance from the standard output of a command. A command which exits unsuccessfully after writing something is an error.
+    #[inline]
+    pub fn command(command: Command) -> RawResponseBuilder<'o, CommandSource> {
+        RawResponseBuilder::new(CommandSource {
+            command,
+            stdin: None,
+        })
+    }
+
+    /// Start building a `RawResponse` instance from a path of a file.
+    #[inline]
+    pub fn file<P: Into<Arc<Path>>>(path: P) -> RawResponseBuilder<'o, FileSource> {
+        RawResponseBuilder::new(FileSource {
+            path: path.into(), precompressed: false
+        })
+    }
+
+    /// Start building a `RawResponse` instance from a path of a file which is opened when the response is responded, so that a handler can return it without awaiting.
+    ///
+    /// Opening the file is blocking I/O, which runs in `block_in_place` on a multi-threaded runtime. Use `RawResponsePro::file` for a slow file system.
+    #[inline]
+    pub fn lazy_file<P: Into<Arc<Path>>>(path: P) -> RawResponseBuilder<'o, LazyFileSource> {
+        RawResponseBuilder::new(LazyFileSource {
+            path: path.into(), precompressed: false
+        })
+    }
+
+    /// Start building a `RawResponse` instance from a `TempFile`.
+    ///
+    /// A file stored on disk is opened by `RawResponseBuilder::open`, or else like a `RawResponsePro::lazy_file`. A missing file is `RawResponseError::NotFound`, or `RawResponseError::Gone` if it has been persisted.
+    #[inline]
+    pub fn temp_file(temp_file: TempFile<'o>) -> RawResponseBuilder<'o, DataSource<'o>> {
+        RawResponseBuilder::new(DataSource {
+            data: RawResponseData::TempFile(Box::new(temp_file), None),
+        })
+    }
+}
@@ -0,0 +1,168 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The seconds from the Unix epoch to 9999-12-31T23:59:59Z, the latest time an HTTP-date can represent.
+const LATEST_HTTP_DATE: u64 = 253_402_300_799;
+
+/// Who is allowed to store a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheVisibility {
+    /// Any cache, including shared caches such as proxies and CDNs (`public`).
+    Public,
+    /// Only the cache of the client (`private`).
+    Private,
+}
+
+/// A caching policy, which is sent as the `Cache-Control` header and, if there is a `max-age`, the `Expires` header.
+///
+/// ```rust
+/// use std::time::Duration;
+///
+/// use rocket_raw_response::CacheControl;
+///
+/// let cache_control =
+///     CacheControl::new().private().max_age(Duration::from_secs(600));
+///
+/// assert_eq!("private, max-age=600", cache_control.to_string());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheControl {
+    visibility:      Option<CacheVisibility>,
+    max_age:         Option<Duration>,
+    no_store:        bool,
+    must_revalidate: bool,
+    immutable:       bool,
+}
+
+impl CacheControl {
+    /// `public, max-age=31536000, immutable`. For assets whose URLs change whenever their content changes.
+    pub const IMMUTABLE: CacheControl =
+        CacheControl::new().public().max_age(Duration::from_secs(31_536_000)).immutable();
+    /// `no-store`. The response is never stored by any cache.
+    pub const NO_STORE: CacheControl = CacheControl::new().no_store();
+    /// `max-age=0, must-revalidate`. The response is stored but checked with the server every time it is used, which works well with entity tags.
+    pub const REVALIDATE: CacheControl =
+        CacheControl::new().max_age(Duration::ZERO).must_revalidate();
+
+    /// Create an empty caching policy. A response with an empty policy gets no `Cache-Control` header.
+    #[inline]
+    pub const fn new() -> CacheControl {
+        CacheControl {
+            visibility:      None,
+            max_age:         None,
+            no_store:        false,
+            must_revalidate: false,
+            immutable:       false,
+        }
+    }
+
+    /// Add `public`.
+    #[inline]
+    pub const fn public(mut self) -> CacheControl {
+        self.visibility = Some(CacheVisibility::Public);
+
+        self
+    }
+
+    /// Add `private`.
+    #[inline]
+    pub const fn private(mut self) -> CacheControl {
+        self.visibility = Some(CacheVisibility::Private);
+
+        self
+    }
+
+    /// Add `max-age`, in seconds. The `Expires` header is also derived from it.
+    #[inline]
+    pub const fn max_age(mut self, max_age: Duration) -> CacheControl {
+        self.max_age = Some(max_age);
+
+        self
+    }
+
+    /// Add `no-store`.
+    #[inline]
+    pub const fn no_store(mut self) -> CacheControl {
+        self.no_store = true;
+
+        self
+    }
+
+    /// Add `must-revalidate`.
+    #[inline]
+    pub const fn must_revalidate(mut self) -> CacheControl {
+        self.must_revalidate = true;
+
+        self
+    }
+
+    /// Add `immutable`.
+    #[inline]
+    pub const fn immutable(mut self) -> CacheControl {
+        self.immutable = true;
+
+        self
+    }
+
+    /// Whether no directive is set, in which case no `Cache-Control` header is sent.
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.visibility.is_none()
+            && !self.no_store
+            && self.max_age.is_none()
+            && !self.must_revalidate
+            && !self.immutable
+    }
+
+    /// The value of the `Expires` header, which is `max-age` seconds after now but no later than the year 9999. Returns `None` if there is no `max-age` or the response must not be stored.
+    pub(crate) fn expires(&self) -> Option<SystemTime> {
+        if self.no_store {
+            return None;
+        }
+
+        let latest = UNIX_EPOCH + Duration::from_secs(LATEST_HTTP_DATE);
+
+        self.max_age.map(|max_age| {
+            SystemTime::now().checked_add(max_age).map_or(latest, |expires| expires.min(latest))
+        })
+    }
+}
+
+impl Default for CacheControl {
+    #[inline]
+    fn default() -> Self {
+        CacheControl::new()
+    }
+}
+
+impl Display for CacheControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        let mut directives = Vec::with_capacity(5);
+
+        match self.visibility {
+            Some(CacheVisibility::Public) => directives.push(String::from("public")),
+            Some(CacheVisibility::Private) => directives.push(String::from("private")),
+            None => (),
+        }
+
+        if self.no_store {
+            directives.push(String::from("no-store"));
+        }
+
+        if let Some(max_age) = self.max_age {
+            directives.push(format!("max-age={}", max_age.as_secs()));
+        }
+
+        if self.must_revalidate {
+            directives.push(String::from("must-revalidate"));
+        }
+
+        if self.immutable {
+            directives.push(String::from("immutable"));
+        }
+
+        f.write_str(&directives.join(", "))
+    }
+}
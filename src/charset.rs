@@ -0,0 +1,122 @@
+use mime::Mime;
+
+/// The number of leading bytes needed by `from_bom`.
+pub(crate) const BOM_LEN: usize = 3;
+
+/// Detect the charset from the byte order mark at the start of the content. Content without a UTF-16 byte order mark is assumed to be UTF-8.
+#[inline]
+pub(crate) fn from_bom(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(b"\xff\xfe") || bytes.starts_with(b"\xfe\xff") {
+        "utf-16"
+    } else {
+        "utf-8"
+    }
+}
+
+/// Append the `charset` parameter to a textual content type which does not have one.
+pub(crate) fn with_charset(content_type: String, charset: &str) -> String {
+    let mime = match content_type.parse::<Mime>() {
+        Ok(mime) => mime,
+        Err(_) => return content_type,
+    };
+
+    if mime.get_param(mime::CHARSET).is_some() || !is_textual(&mime) {
+        return content_type;
+    }
+
+    format!("{}; charset={}", content_type, charset)
+}
+
+/// Whether a content type is text which can have a `charset` parameter.
+pub(crate) fn is_textual(mime: &Mime) -> bool {
+    if mime.type_() == mime::TEXT {
+        return true;
+    }
+
+    if let Some(suffix) = mime.suffix() {
+        if suffix == mime::JSON || suffix == mime::XML {
+            return true;
+        }
+    }
+
+    mime.type_() == mime::APPLICATION
+        && matches!(
+            mime.subtype().as_str(),
+            "json"
+                | "javascript"
+                | "ecmascript"
+                | "x-javascript"
+                | "xml"
+                | "xml-dtd"
+                | "x-sh"
+                | "x-csh"
+                | "sql"
+                | "toml"
+                | "yaml"
+                | "x-yaml"
+                | "graphql"
+                | "rtf"
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn charset_from_bom() {
+        assert_eq!("utf-16", from_bom(b"\xff\xfeh\x00"));
+        assert_eq!("utf-16", from_bom(b"\xfe\xff\x00h"));
+        assert_eq!("utf-8", from_bom(b"\xef\xbb\xbfh"));
+        assert_eq!("utf-8", from_bom(b"h"));
+        assert_eq!("utf-8", from_bom(b""));
+    }
+
+    #[test]
+    fn textual_content_types() {
+        for content_type in [
+            "text/plain",
+            "text/csv",
+            "application/json",
+            "application/javascript",
+            "application/ld+json",
+            "image/svg+xml",
+            "application/yaml",
+        ] {
+            assert!(is_textual(&content_type.parse().unwrap()), "{}", content_type);
+        }
+
+        for content_type in
+            ["image/png", "application/octet-stream", "application/pdf", "application/zip"]
+        {
+            assert!(!is_textual(&content_type.parse().unwrap()), "{}", content_type);
+        }
+    }
+
+    #[test]
+    fn append_charset() {
+        assert_eq!("text/plain; charset=utf-8", with_charset("text/plain".to_string(), "utf-8"));
+        assert_eq!(
+            "application/json; charset=utf-16",
+            with_charset("application/json".to_string(), "utf-16")
+        );
+        assert_eq!(
+            "text/csv; header=present; charset=utf-8",
+            with_charset("text/csv; header=present".to_string(), "utf-8")
+        );
+    }
+
+    #[test]
+    fn keep_charset() {
+        assert_eq!(
+            "text/plain; charset=iso-8859-1",
+            with_charset("text/plain; charset=iso-8859-1".to_string(), "utf-8")
+        );
+        assert_eq!(
+            "text/html; Charset=\"Shift_JIS\"",
+            with_charset("text/html; Charset=\"Shift_JIS\"".to_string(), "utf-8")
+        );
+        assert_eq!("image/png", with_charset("image/png".to_string(), "utf-8"));
+        assert_eq!("not a content type", with_charset("not a content type".to_string(), "utf-8"));
+    }
+}
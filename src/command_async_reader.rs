@@ -0,0 +1,175 @@
+use std::{
+    future::Future,
+    io::{self, Cursor},
+    pin::Pin,
+    process::{ExitStatus, Stdio},
+    task::{ready, Context, Poll},
+};
+
+use rocket::tokio::{
+    io::{AsyncRead, AsyncReadExt, ReadBuf},
+    process::{Child, ChildStdout, Command},
+};
+
+use crate::RawResponseError;
+
+/// The size of the first chunk awaited before a command is responded.
+const FIRST_CHUNK_SIZE: usize = 8 * 1024;
+
+enum State {
+    Reading(Child),
+    Waiting(Pin<Box<dyn Future<Output = io::Result<ExitStatus>> + Send>>),
+    Done,
+}
+
+/// Read the standard output of a child process. The process is killed when the reader is dropped, and a non-zero exit status is returned as a read error after its output.
+pub(crate) struct CommandAsyncReader {
+    stdout: ChildStdout,
+    state:  State,
+}
+
+/// Spawn a command and wait for its first output. A command which exits unsuccessfully without any output fails with `RawResponseError::CommandFailed`.
+pub(crate) async fn spawn(
+    mut command: Command,
+) -> Result<impl AsyncRead + Send + Unpin, RawResponseError> {
+    command.stdout(Stdio::piped()).kill_on_drop(true);
+
+    let mut child = command.spawn().map_err(RawResponseError::Io)?;
+
+    let mut stdout = child.stdout.take().ok_or_else(|| {
+        RawResponseError::Io(io::Error::new(io::ErrorKind::Other, "the stdout is not piped"))
+    })?;
+
+    let mut buffer = vec![0; FIRST_CHUNK_SIZE];
+
+    let len = stdout.read(&mut buffer).await.map_err(RawResponseError::Io)?;
+
+    if len == 0 {
+        let status = child.wait().await.map_err(RawResponseError::Io)?;
+
+        if !status.success() {
+            return Err(RawResponseError::CommandFailed(status));
+        }
+    }
+
+    buffer.truncate(len);
+
+    Ok(Cursor::new(buffer).chain(CommandAsyncReader {
+        stdout,
+        state: State::Reading(child),
+    }))
+}
+
+impl AsyncRead for CommandAsyncReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), io::Error>> {
+        loop {
+            match &mut self.state {
+                State::Reading(_) => {
+                    let filled = buf.filled().len();
+
+                    ready!(Pin::new(&mut self.stdout).poll_read(ctx, buf))?;
+
+                    if buf.filled().len() > filled || buf.remaining() == 0 {
+                        return Poll::Ready(Ok(()));
+                    }
+
+                    if let State::Reading(mut child) =
+                        std::mem::replace(&mut self.state, State::Done)
+                    {
+                        self.state = State::Waiting(Box::pin(async move { child.wait().await }));
+                    }
+                },
+                State::Waiting(exit) => {
+                    let status = ready!(exit.as_mut().poll(ctx))?;
+
+                    self.state = State::Done;
+
+                    if !status.success() {
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            format!("the command exited with {}", status),
+                        )));
+                    }
+                },
+                State::Done => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::time::Duration;
+
+    use rocket::tokio::time;
+
+    use super::*;
+
+    fn sh(script: &str) -> Command {
+        let mut command = Command::new("sh");
+
+        command.arg("-c").arg(script).stdin(Stdio::null());
+
+        command
+    }
+
+    #[rocket::async_test]
+    async fn read_until_exit() {
+        let mut reader = spawn(sh("printf hello")).await.unwrap();
+        let mut data = Vec::new();
+
+        reader.read_to_end(&mut data).await.unwrap();
+
+        assert_eq!(b"hello", data.as_slice());
+    }
+
+    #[rocket::async_test]
+    async fn read_until_failure() {
+        let mut reader = spawn(sh("printf hi; exit 3")).await.unwrap();
+        let mut data = [0; 2];
+
+        reader.read_exact(&mut data).await.unwrap();
+
+        assert_eq!(b"hi", &data);
+        assert!(reader.read(&mut data).await.is_err());
+    }
+
+    #[rocket::async_test]
+    async fn fail_without_output() {
+        match spawn(sh("exit 3")).await {
+            Err(RawResponseError::CommandFailed(status)) => assert_eq!(Some(3), status.code()),
+            Err(error) => panic!("unexpected error: {}", error),
+            Ok(_) => panic!("the command should fail"),
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[rocket::async_test]
+    async fn kill_when_dropped() {
+        let mut reader = spawn(sh("echo $$; exec sleep 30")).await.unwrap();
+        let mut data = [0; 32];
+
+        let len = reader.read(&mut data).await.unwrap();
+        let pid = std::str::from_utf8(&data[..len]).unwrap().trim().to_string();
+
+        drop(reader);
+
+        // a killed process which has not been reaped yet is a zombie
+        let is_running = || match std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+            Ok(stat) => !stat.rsplit(") ").next().unwrap_or("").starts_with('Z'),
+            Err(_) => false,
+        };
+
+        time::timeout(Duration::from_secs(5), async {
+            while is_running() {
+                time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+}
@@ -0,0 +1,251 @@
+use std::marker::Unpin;
+
+use rocket::{
+    request::Request,
+    tokio::io::{AsyncRead, BufReader},
+};
+
+use crate::accept_encoding;
+
+/// A content coding which can be applied to a response body on the fly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    #[cfg(feature = "brotli")]
+    Brotli,
+    #[cfg(feature = "zstd")]
+    Zstd,
+    #[cfg(feature = "gzip")]
+    Gzip,
+}
+
+impl Encoding {
+    /// The enabled encodings, in the order of preference.
+    const ALL: &'static [Encoding] = &[
+        #[cfg(feature = "brotli")]
+        Encoding::Brotli,
+        #[cfg(feature = "zstd")]
+        Encoding::Zstd,
+        #[cfg(feature = "gzip")]
+        Encoding::Gzip,
+    ];
+    /// The values of the `Content-Encoding` header of `ALL`.
+    const CODINGS: &'static [&'static str] = &[
+        #[cfg(feature = "brotli")]
+        "br",
+        #[cfg(feature = "zstd")]
+        "zstd",
+        #[cfg(feature = "gzip")]
+        "gzip",
+    ];
+
+    /// The value of the `Content-Encoding` header.
+    #[inline]
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            #[cfg(feature = "brotli")]
+            Encoding::Brotli => "br",
+            #[cfg(feature = "zstd")]
+            Encoding::Zstd => "zstd",
+            #[cfg(feature = "gzip")]
+            Encoding::Gzip => "gzip",
+        }
+    }
+
+    /// Choose an encoding from the `Accept-Encoding` header of a request, or `None` for the identity.
+    ///
+    /// Content types which are already compressed are not encoded again, and requests with a `Range` header always get the identity, so that the ranges still refer to the original bytes.
+    pub(crate) fn from_request(
+        request: &Request<'_>,
+        content_type: Option<&str>,
+    ) -> Option<Encoding> {
+        if request.headers().contains("Range") || !is_compressible(content_type) {
+            return None;
+        }
+
+        let coding = accept_encoding::preferred(request, Encoding::CODINGS)?;
+
+        Encoding::ALL.iter().copied().find(|encoding| encoding.as_str() == coding)
+    }
+
+    /// Wrap a reader so that it produces encoded data.
+    #[allow(unused_variables)]
+    pub(crate) fn encode<'o, R: AsyncRead + Send + Unpin + 'o>(
+        self,
+        reader: R,
+    ) -> Box<dyn AsyncRead + Send + Unpin + 'o> {
+        let reader = BufReader::new(reader);
+
+        match self {
+            #[cfg(feature = "brotli")]
+            Encoding::Brotli => {
+                Box::new(async_compression::tokio::bufread::BrotliEncoder::new(reader))
+            },
+            #[cfg(feature = "zstd")]
+            Encoding::Zstd => Box::new(async_compression::tokio::bufread::ZstdEncoder::new(reader)),
+            #[cfg(feature = "gzip")]
+            Encoding::Gzip => Box::new(async_compression::tokio::bufread::GzipEncoder::new(reader)),
+        }
+    }
+}
+
+/// Whether a content type is worth compressing. Images, audio, video, fonts and archives are usually compressed already.
+fn is_compressible(content_type: Option<&str>) -> bool {
+    let essence = match content_type {
+        Some(content_type) => {
+            content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase()
+        },
+        None => return true,
+    };
+
+    if let Some(subtype) = essence.strip_prefix("image/") {
+        return matches!(subtype, "svg+xml" | "bmp" | "x-icon" | "vnd.microsoft.icon");
+    }
+
+    if essence.starts_with("video/")
+        || essence.starts_with("audio/")
+        || essence.starts_with("font/woff")
+        || essence.ends_with("+zip")
+        || essence.starts_with("application/vnd.openxmlformats-officedocument.")
+    {
+        return false;
+    }
+
+    !matches!(
+        essence.as_str(),
+        "application/zip"
+            | "application/gzip"
+            | "application/x-gzip"
+            | "application/x-bzip2"
+            | "application/x-xz"
+            | "application/x-7z-compressed"
+            | "application/vnd.rar"
+            | "application/x-rar-compressed"
+            | "application/zstd"
+            | "application/x-brotli"
+            | "application/java-archive"
+            | "application/vnd.android.package-archive"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressible_content_types() {
+        assert!(is_compressible(None));
+        assert!(is_compressible(Some("text/html; charset=utf-8")));
+        assert!(is_compressible(Some("application/json")));
+        assert!(is_compressible(Some("image/svg+xml")));
+        assert!(is_compressible(Some("IMAGE/BMP")));
+
+        assert!(!is_compressible(Some("image/png")));
+        assert!(!is_compressible(Some("image/jpeg")));
+        assert!(!is_compressible(Some("video/mp4")));
+        assert!(!is_compressible(Some("audio/mpeg")));
+        assert!(!is_compressible(Some("font/woff2")));
+        assert!(!is_compressible(Some("application/zip")));
+        assert!(!is_compressible(Some("application/epub+zip")));
+        assert!(!is_compressible(Some(
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        )));
+    }
+
+    #[cfg(feature = "gzip")]
+    mod gzip {
+        use rocket::{http::Status, tokio::io::AsyncReadExt};
+
+        use crate::{
+            mime,
+            testing::{client, respond},
+            RawResponse,
+        };
+
+        const TEXT: &str = "Lorem ipsum dolor sit amet. ";
+
+        #[rocket::async_test]
+        async fn respond_compressed() {
+            let client = client().await;
+            let text = TEXT.repeat(100);
+
+            let mut response = respond(
+                client.get("/"),
+                &[("Accept-Encoding", "gzip")],
+                RawResponse::vec(text.clone().into_bytes())
+                    .content_type(mime::TEXT_PLAIN)
+                    .compress(true)
+                    .build(),
+            );
+
+            assert_eq!(Status::Ok, response.status());
+            assert_eq!(Some("gzip"), response.headers().get_one("Content-Encoding"));
+            assert_eq!(Some("Accept-Encoding"), response.headers().get_one("Vary"));
+            assert_eq!(None, response.headers().get_one("Content-Length"));
+            assert_eq!(None, response.body().preset_size());
+
+            let body = response.body_mut().to_bytes().await.unwrap();
+
+            assert!(body.len() < text.len());
+
+            let mut decoded = String::new();
+
+            async_compression::tokio::bufread::GzipDecoder::new(body.as_slice())
+                .read_to_string(&mut decoded)
+                .await
+                .unwrap();
+
+            assert_eq!(text, decoded);
+        }
+
+        #[rocket::async_test]
+        async fn respond_already_compressed_content_type() {
+            let client = client().await;
+
+            let response = respond(
+                client.get("/"),
+                &[("Accept-Encoding", "gzip")],
+                RawResponse::slice(TEXT.as_bytes())
+                    .content_type(mime::IMAGE_PNG)
+                    .compress(true)
+                    .build(),
+            );
+
+            assert_eq!(None, response.headers().get_one("Content-Encoding"));
+            assert_eq!(Some("Accept-Encoding"), response.headers().get_one("Vary"));
+            assert_eq!(Some(TEXT.len()), response.body().preset_size());
+        }
+
+        #[rocket::async_test]
+        async fn respond_range_uncompressed() {
+            let client = client().await;
+
+            let mut response = respond(
+                client.get("/"),
+                &[("Accept-Encoding", "gzip"), ("Range", "bytes=0-4")],
+                RawResponse::slice(TEXT.as_bytes())
+                    .content_type(mime::TEXT_PLAIN)
+                    .compress(true)
+                    .build(),
+            );
+
+            assert_eq!(Status::PartialContent, response.status());
+            assert_eq!(None, response.headers().get_one("Content-Encoding"));
+            assert_eq!(Some("Accept-Encoding"), response.headers().get_one("Vary"));
+            assert_eq!(b"Lorem", response.body_mut().to_bytes().await.unwrap().as_slice());
+        }
+
+        #[rocket::async_test]
+        async fn respond_uncompressed_without_compress() {
+            let client = client().await;
+
+            let response = respond(
+                client.get("/"),
+                &[("Accept-Encoding", "gzip")],
+                RawResponse::slice(TEXT.as_bytes()).content_type(mime::TEXT_PLAIN).build(),
+            );
+
+            assert_eq!(None, response.headers().get_one("Content-Encoding"));
+            assert_eq!(None, response.headers().get_one("Vary"));
+        }
+    }
+}
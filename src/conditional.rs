@@ -0,0 +1,298 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rocket::{http::Method, request::Request};
+
+use crate::etag::{self, EntityTag};
+
+/// The validators of a representation.
+#[derive(Debug, Default)]
+pub(crate) struct Validators {
+    pub(crate) etag:          Option<EntityTag>,
+    pub(crate) last_modified: Option<SystemTime>,
+}
+
+impl Validators {
+    /// Create validators. The last modification time is truncated to seconds and never later than now. A time before the Unix epoch cannot be an HTTP-date, so it is dropped.
+    #[inline]
+    pub(crate) fn new(etag: Option<EntityTag>, last_modified: Option<SystemTime>) -> Validators {
+        Validators {
+            etag,
+            last_modified: last_modified
+                .filter(|mtime| *mtime >= UNIX_EPOCH)
+                .map(|mtime| truncate(mtime.min(SystemTime::now()))),
+        }
+    }
+
+    /// Whether the `If-Range` header of a request, if any, allows the `Range` header to be used. An entity tag must match with the strong comparison and a date must be exactly the last modification time.
+    pub(crate) fn if_range(&self, request: &Request<'_>) -> bool {
+        let value = match request.headers().get_one("If-Range") {
+            Some(value) => value.trim(),
+            None => return true,
+        };
+
+        if value.starts_with('"') || value.starts_with("W/") {
+            etag::list_matches(value, |other| {
+                self.etag.as_ref().map_or(false, |etag| etag.strong_eq(other))
+            })
+        } else {
+            match (parse_http_date(value), self.last_modified) {
+                (Some(date), Some(last_modified)) => date == last_modified,
+                _ => false,
+            }
+        }
+    }
+}
+
+/// The result of evaluating the conditional headers of a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Precondition {
+    /// The request should be answered normally.
+    Passed,
+    /// The request should be answered with `304 Not Modified`.
+    NotModified,
+    /// The request should be answered with `412 Precondition Failed`.
+    Failed,
+}
+
+impl Precondition {
+    /// Evaluate `If-Match`, `If-Unmodified-Since`, `If-None-Match` and `If-Modified-Since` in the order defined by RFC 9110, section 13.2.2.
+    pub(crate) fn from_request(request: &Request<'_>, validators: &Validators) -> Precondition {
+        let headers = request.headers();
+        let etag = validators.etag.as_ref();
+        let last_modified = validators.last_modified;
+
+        if let Some(value) = headers.get_one("If-Match") {
+            if !etag::list_matches(value, |other| etag.map_or(false, |etag| etag.strong_eq(other)))
+            {
+                return Precondition::Failed;
+            }
+        } else if let Some(value) = headers.get_one("If-Unmodified-Since") {
+            if let (Some(date), Some(last_modified)) = (parse_http_date(value), last_modified) {
+                if last_modified > date {
+                    return Precondition::Failed;
+                }
+            }
+        }
+
+        let is_get_or_head = matches!(request.method(), Method::Get | Method::Head);
+
+        if let Some(value) = headers.get_one("If-None-Match") {
+            if etag::list_matches(value, |other| etag.map_or(false, |etag| etag.weak_eq(other))) {
+                return if is_get_or_head {
+                    Precondition::NotModified
+                } else {
+                    Precondition::Failed
+                };
+            }
+        } else if let Some(value) = headers.get_one("If-Modified-Since") {
+            if is_get_or_head {
+                if let (Some(date), Some(last_modified)) = (parse_http_date(value), last_modified) {
+                    if last_modified <= date {
+                        return Precondition::NotModified;
+                    }
+                }
+            }
+        }
+
+        Precondition::Passed
+    }
+}
+
+/// Format a time as an HTTP-date, which is also the value of the `Last-Modified` header.
+#[inline]
+pub(crate) fn fmt_http_date(time: SystemTime) -> String {
+    httpdate::fmt_http_date(time)
+}
+
+#[inline]
+pub(crate) fn parse_http_date(value: &str) -> Option<SystemTime> {
+    httpdate::parse_http_date(value.trim()).ok()
+}
+
+/// Drop the sub-second part of a time, because an HTTP-date has a resolution of one second.
+#[inline]
+fn truncate(time: SystemTime) -> SystemTime {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => UNIX_EPOCH + Duration::from_secs(duration.as_secs()),
+        Err(_) => time,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::{
+        http::Header,
+        local::blocking::{Client, LocalRequest},
+    };
+
+    use super::*;
+    use crate::testing::blocking_client;
+
+    const DATE: &str = "Sun, 06 Nov 1994 08:49:37 GMT";
+    const EARLIER: &str = "Sun, 06 Nov 1994 08:49:36 GMT";
+
+    fn validators() -> Validators {
+        Validators {
+            etag: EntityTag::strong("v1"), last_modified: parse_http_date(DATE)
+        }
+    }
+
+    fn evaluate(
+        mut request: LocalRequest<'_>,
+        headers: &[(&'static str, &'static str)],
+    ) -> Precondition {
+        for (name, value) in headers {
+            request = request.header(Header::new(*name, *value));
+        }
+
+        Precondition::from_request(request.inner(), &validators())
+    }
+
+    #[test]
+    fn precondition_if_match() {
+        let client = blocking_client();
+
+        assert_eq!(Precondition::Passed, evaluate(client.get("/"), &[("If-Match", r#""v1""#)]));
+        assert_eq!(Precondition::Passed, evaluate(client.get("/"), &[("If-Match", "*")]));
+        assert_eq!(Precondition::Failed, evaluate(client.get("/"), &[("If-Match", r#""v2""#)]));
+        assert_eq!(Precondition::Failed, evaluate(client.get("/"), &[("If-Match", r#"W/"v1""#)]));
+    }
+
+    #[test]
+    fn precondition_if_match_takes_precedence_over_if_unmodified_since() {
+        let client = blocking_client();
+
+        assert_eq!(
+            Precondition::Passed,
+            evaluate(client.get("/"), &[("If-Match", r#""v1""#), ("If-Unmodified-Since", EARLIER)])
+        );
+        assert_eq!(
+            Precondition::Failed,
+            evaluate(client.get("/"), &[("If-Unmodified-Since", EARLIER)])
+        );
+        assert_eq!(
+            Precondition::Passed,
+            evaluate(client.get("/"), &[("If-Unmodified-Since", DATE)])
+        );
+        assert_eq!(
+            Precondition::Passed,
+            evaluate(client.get("/"), &[("If-Unmodified-Since", "not a date")])
+        );
+    }
+
+    #[test]
+    fn precondition_if_none_match() {
+        let client = blocking_client();
+
+        assert_eq!(
+            Precondition::NotModified,
+            evaluate(client.get("/"), &[("If-None-Match", r#"W/"v1""#)])
+        );
+        assert_eq!(
+            Precondition::NotModified,
+            evaluate(client.head("/"), &[("If-None-Match", "*")])
+        );
+        assert_eq!(Precondition::Failed, evaluate(client.post("/"), &[("If-None-Match", "*")]));
+        assert_eq!(
+            Precondition::Passed,
+            evaluate(client.get("/"), &[("If-None-Match", r#""v2""#)])
+        );
+    }
+
+    #[test]
+    fn precondition_if_none_match_takes_precedence_over_if_modified_since() {
+        let client = blocking_client();
+
+        assert_eq!(
+            Precondition::Passed,
+            evaluate(client.get("/"), &[("If-None-Match", r#""v2""#), ("If-Modified-Since", DATE)])
+        );
+        assert_eq!(
+            Precondition::NotModified,
+            evaluate(client.get("/"), &[("If-Modified-Since", DATE)])
+        );
+        assert_eq!(
+            Precondition::Passed,
+            evaluate(client.get("/"), &[("If-Modified-Since", EARLIER)])
+        );
+        assert_eq!(
+            Precondition::Passed,
+            evaluate(client.post("/"), &[("If-Modified-Since", DATE)])
+        );
+    }
+
+    #[test]
+    fn precondition_failure_wins_over_not_modified() {
+        let client = blocking_client();
+
+        assert_eq!(
+            Precondition::Failed,
+            evaluate(client.get("/"), &[("If-Match", r#""v2""#), ("If-None-Match", r#""v1""#)])
+        );
+        assert_eq!(
+            Precondition::Failed,
+            evaluate(client.get("/"), &[
+                ("If-Unmodified-Since", EARLIER),
+                ("If-Modified-Since", DATE)
+            ])
+        );
+    }
+
+    fn if_range(client: &Client, validators: &Validators, value: &'static str) -> bool {
+        let request = client.get("/").header(Header::new("If-Range", value));
+
+        validators.if_range(request.inner())
+    }
+
+    #[test]
+    fn if_range_without_header() {
+        let client = blocking_client();
+
+        assert!(validators().if_range(client.get("/").inner()));
+    }
+
+    #[test]
+    fn if_range_entity_tags() {
+        let client = blocking_client();
+
+        assert!(if_range(&client, &validators(), r#""v1""#));
+        assert!(!if_range(&client, &validators(), r#""v2""#));
+        assert!(!if_range(&client, &validators(), r#"W/"v1""#));
+
+        let weak = Validators {
+            etag: EntityTag::weak("v1"), last_modified: None
+        };
+
+        assert!(!if_range(&client, &weak, r#"W/"v1""#));
+        assert!(!if_range(&client, &weak, r#""v1""#));
+        assert!(!if_range(&client, &Validators::default(), r#""v1""#));
+    }
+
+    #[test]
+    fn validators_last_modified() {
+        let now = SystemTime::now();
+        let before_epoch = UNIX_EPOCH - Duration::from_secs(10 * 365 * 24 * 60 * 60);
+
+        let last_modified = |mtime| Validators::new(None, Some(mtime)).last_modified;
+
+        assert_eq!(parse_http_date(DATE), last_modified(parse_http_date(DATE).unwrap()));
+        assert_eq!(
+            parse_http_date(DATE),
+            last_modified(parse_http_date(DATE).unwrap() + Duration::from_millis(500))
+        );
+        assert!(last_modified(now + Duration::from_secs(3600)).unwrap() <= SystemTime::now());
+        assert_eq!(None, last_modified(before_epoch));
+        assert_eq!(Some(UNIX_EPOCH), last_modified(UNIX_EPOCH));
+    }
+
+    #[test]
+    fn if_range_dates() {
+        let client = blocking_client();
+
+        assert!(if_range(&client, &validators(), DATE));
+        assert!(!if_range(&client, &validators(), EARLIER));
+        assert!(!if_range(&client, &validators(), "Sun, 06 Nov 1994 08:49:38 GMT"));
+        assert!(!if_range(&client, &validators(), "not a date"));
+        assert!(!if_range(&client, &Validators::default(), DATE));
+    }
+}
@@ -0,0 +1,155 @@
+use url_escape::percent_encoding::AsciiSet;
+
+/// The bytes which are percent-encoded in an `ext-value`, i.e. everything but `attr-char` (RFC 8187).
+const EXT_VALUE: &AsciiSet = &url_escape::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'%')
+    .add(b'\'')
+    .add(b'(')
+    .add(b')')
+    .add(b'*')
+    .add(b',')
+    .add(b'/')
+    .add(b':')
+    .add(b';')
+    .add(b'<')
+    .add(b'=')
+    .add(b'>')
+    .add(b'?')
+    .add(b'@')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'{')
+    .add(b'}');
+
+/// The disposition type of the `Content-Disposition` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Disposition {
+    /// Let the client display the content, e.g. as a preview in the browser.
+    #[default]
+    Inline,
+    /// Make the client save the content as a file, e.g. with a download dialog.
+    Attachment,
+    /// Do not send the `Content-Disposition` header.
+    None,
+}
+
+impl Disposition {
+    /// Create the value of the `Content-Disposition` header. Returns `None` for `Disposition::None`.
+    ///
+    /// If the file name is known and not empty, a transliterated ASCII `filename` parameter is added for old clients, followed by a `filename*` parameter in UTF-8 (RFC 6266).
+    pub(crate) fn to_header_value(self, file_name: Option<&str>) -> Option<String> {
+        let mut v = match self {
+            Disposition::Inline => String::from("inline"),
+            Disposition::Attachment => String::from("attachment"),
+            Disposition::None => return None,
+        };
+
+        if let Some(file_name) = file_name {
+            if !file_name.is_empty() {
+                let ascii_file_name = ascii_file_name(file_name);
+
+                if !ascii_file_name.is_empty() {
+                    v.push_str("; filename=\"");
+                    v.push_str(&ascii_file_name);
+                    v.push('"');
+                }
+
+                v.push_str("; filename*=UTF-8''");
+
+                url_escape::encode_to_string(file_name, EXT_VALUE, &mut v);
+            }
+        }
+
+        Some(v)
+    }
+}
+
+/// Transliterate a file name into printable ASCII which can be put in a quoted-string.
+///
+/// Path separators and `%` (which some clients decode) become `_`, control characters are dropped, and `"` is escaped. Returns an empty string if nothing meaningful is left.
+fn ascii_file_name(file_name: &str) -> String {
+    let transliterated = deunicode::deunicode_with_tofu(file_name, "_");
+
+    let mut s = String::with_capacity(transliterated.len());
+
+    for c in transliterated.trim().chars() {
+        match c {
+            '"' => s.push_str("\\\""),
+            '/' | '\\' | '%' => s.push('_'),
+            ' '..='~' => s.push(c),
+            _ => (),
+        }
+    }
+
+    if s.chars().all(|c| c == '_' || c == '.' || c == ' ') {
+        s.clear();
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_file_name_escapes_and_replaces() {
+        assert_eq!("report.pdf", ascii_file_name("report.pdf"));
+        assert_eq!("a \\\"quoted\\\" name.txt", ascii_file_name("a \"quoted\" name.txt"));
+        assert_eq!("100_ done.txt", ascii_file_name("100% done.txt"));
+        assert_eq!("_etc_passwd", ascii_file_name("/etc/passwd"));
+        assert_eq!("a_b.txt", ascii_file_name("a\\b.txt"));
+        assert_eq!("ab.txt", ascii_file_name("a\r\nb.txt"));
+        assert_eq!("Cafe.txt", ascii_file_name(" Café.txt "));
+    }
+
+    #[test]
+    fn ascii_file_name_without_meaningful_characters() {
+        assert_eq!("", ascii_file_name(""));
+        assert_eq!("", ascii_file_name("."));
+        assert_eq!("", ascii_file_name(".."));
+        assert_eq!("", ascii_file_name("..."));
+        assert_eq!("", ascii_file_name("/"));
+        assert_eq!("", ascii_file_name("%%"));
+    }
+
+    #[test]
+    fn header_value() {
+        assert_eq!(None, Disposition::None.to_header_value(Some("a.txt")));
+        assert_eq!(Some("inline".to_string()), Disposition::Inline.to_header_value(None));
+        assert_eq!(
+            Some("attachment".to_string()),
+            Disposition::Attachment.to_header_value(Some(""))
+        );
+        assert_eq!(
+            Some("attachment; filename=\"a b.txt\"; filename*=UTF-8''a%20b.txt".to_string()),
+            Disposition::Attachment.to_header_value(Some("a b.txt"))
+        );
+        assert_eq!(
+            Some(
+                "attachment; filename=\"it's (1)*.pdf\"; filename*=UTF-8''it%27s%20%281%29%2A.pdf"
+                    .to_string()
+            ),
+            Disposition::Attachment.to_header_value(Some("it's (1)*.pdf"))
+        );
+        assert_eq!(
+            Some(
+                "inline; filename=\"Yue Bao ;,.pdf\"; \
+                 filename*=UTF-8''%E6%9C%88%E5%A0%B1%20%3B%2C.pdf"
+                    .to_string()
+            ),
+            Disposition::Inline.to_header_value(Some("月報 ;,.pdf"))
+        );
+        assert_eq!(
+            Some("inline; filename=\"a!#$&+-.^_`|~\"; filename*=UTF-8''a!#$&+-.^_`|~".to_string()),
+            Disposition::Inline.to_header_value(Some("a!#$&+-.^_`|~"))
+        );
+        assert_eq!(
+            Some("inline; filename*=UTF-8''..".to_string()),
+            Disposition::Inline.to_header_value(Some(".."))
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use mime::Mime;
+use rocket::http::Status;
+
+/// A policy deciding which content types may be responded at all. A response with a rejected content type fails with the rejection status instead of sending its body.
+///
+/// Denied types always win. Once a type is allowed, only allowed types are responded, and a response without a content type is rejected. Subtypes can be wildcards, e.g. `text/*`, and parameters are ignored.
+///
+/// For an uploaded file, the content type resolved from the extension of its file name is checked as well as the responded one, so a file cannot pass by declaring a harmless content type.
+///
+/// ```rust
+/// use rocket::http::Status;
+/// use rocket_raw_response::{mime, ContentTypePolicy};
+///
+/// let content_type_policy = ContentTypePolicy::new()
+///     .deny("application/x-msdownload".parse().unwrap())
+///     .deny(mime::TEXT_JAVASCRIPT)
+///     .rejection_status(Status::UnsupportedMediaType);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentTypePolicy {
+    allowed:          Vec<Mime>,
+    denied:           Vec<Mime>,
+    rejection_status: Status,
+}
+
+impl ContentTypePolicy {
+    /// Create a policy which allows every content type and rejects with `403 Forbidden`.
+    #[inline]
+    pub fn new() -> ContentTypePolicy {
+        ContentTypePolicy {
+            allowed:          Vec::new(),
+            denied:           Vec::new(),
+            rejection_status: Status::Forbidden,
+        }
+    }
+
+    /// Allow a content type. Other content types are rejected unless they are allowed too.
+    #[inline]
+    pub fn allow(mut self, content_type: Mime) -> ContentTypePolicy {
+        self.allowed.push(content_type);
+
+        self
+    }
+
+    /// Deny a content type.
+    #[inline]
+    pub fn deny(mut self, content_type: Mime) -> ContentTypePolicy {
+        self.denied.push(content_type);
+
+        self
+    }
+
+    /// Set the status of a rejected response. The default value is `Status::Forbidden`.
+    #[inline]
+    pub fn rejection_status(mut self, rejection_status: Status) -> ContentTypePolicy {
+        self.rejection_status = rejection_status;
+
+        self
+    }
+
+    /// Check a content type. Returns the rejection status if it is not permitted.
+    pub(crate) fn check(&self, content_type: Option<&str>) -> Result<(), Status> {
+        let mime = content_type.and_then(|content_type| content_type.parse::<Mime>().ok());
+
+        let permitted = match mime {
+            Some(mime) => {
+                !self.denied.iter().any(|pattern| matches(pattern, &mime))
+                    && (self.allowed.is_empty()
+                        || self.allowed.iter().any(|pattern| matches(pattern, &mime)))
+            },
+            None => self.allowed.is_empty(),
+        };
+
+        if permitted {
+            Ok(())
+        } else {
+            Err(self.rejection_status)
+        }
+    }
+}
+
+impl Default for ContentTypePolicy {
+    #[inline]
+    fn default() -> Self {
+        ContentTypePolicy::new()
+    }
+}
+
+#[inline]
+fn matches(pattern: &Mime, mime: &Mime) -> bool {
+    (pattern.type_() == mime::STAR || pattern.type_() == mime.type_())
+        && (pattern.subtype() == mime::STAR || pattern.essence_str() == mime.essence_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::{
+        fs::{FileName, TempFile},
+        http::ContentType,
+        response::Responder,
+        Either,
+    };
+
+    use super::*;
+    use crate::{testing::client, RawResponse};
+
+    #[test]
+    fn check_denied() {
+        let policy =
+            ContentTypePolicy::new().deny(mime::TEXT_HTML).deny("video/*".parse().unwrap());
+
+        assert_eq!(Err(Status::Forbidden), policy.check(Some("text/html")));
+        assert_eq!(Err(Status::Forbidden), policy.check(Some("TEXT/HTML; charset=utf-8")));
+        assert_eq!(Err(Status::Forbidden), policy.check(Some("video/mp4")));
+        assert_eq!(Ok(()), policy.check(Some("text/plain")));
+        assert_eq!(Ok(()), policy.check(None));
+    }
+
+    #[test]
+    fn check_allowed() {
+        let policy =
+            ContentTypePolicy::new().allow("text/*".parse().unwrap()).allow(mime::IMAGE_PNG);
+
+        assert_eq!(Ok(()), policy.check(Some("text/plain; charset=utf-8")));
+        assert_eq!(Ok(()), policy.check(Some("text/csv")));
+        assert_eq!(Ok(()), policy.check(Some("image/png")));
+        assert_eq!(Err(Status::Forbidden), policy.check(Some("image/jpeg")));
+        assert_eq!(Err(Status::Forbidden), policy.check(Some("application/text")));
+        assert_eq!(Err(Status::Forbidden), policy.check(Some("not a content type")));
+        assert_eq!(Err(Status::Forbidden), policy.check(None));
+    }
+
+    #[test]
+    fn check_denied_wins_over_allowed() {
+        let policy = ContentTypePolicy::new()
+            .allow("text/*".parse().unwrap())
+            .deny(mime::TEXT_HTML)
+            .rejection_status(Status::UnsupportedMediaType);
+
+        assert_eq!(Ok(()), policy.check(Some("text/plain")));
+        assert_eq!(Err(Status::UnsupportedMediaType), policy.check(Some("text/html")));
+
+        let policy = ContentTypePolicy::new().allow(mime::TEXT_HTML).deny("*/*".parse().unwrap());
+
+        assert_eq!(Err(Status::Forbidden), policy.check(Some("text/html")));
+    }
+
+    #[rocket::async_test]
+    async fn respond_upload_with_declared_content_type() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("upload");
+
+        std::fs::write(&path, "<script>alert(1);</script>").unwrap();
+
+        let upload = |file_name: &'static str| TempFile::File {
+            file_name:    Some(FileName::new(file_name)),
+            content_type: Some(ContentType::PNG),
+            path:         Either::Right(path.clone()),
+            len:          26,
+        };
+
+        let policy = ContentTypePolicy::new().allow("image/*".parse().unwrap());
+
+        let response = RawResponse::temp_file(upload("x.html"))
+            .content_type_policy(policy.clone())
+            .open()
+            .await
+            .unwrap()
+            .build()
+            .respond_to(client.get("/").inner());
+
+        assert_eq!(Some(Status::Forbidden), response.err());
+
+        let response = RawResponse::temp_file(upload("x.png"))
+            .content_type_policy(policy)
+            .open()
+            .await
+            .unwrap()
+            .build()
+            .respond_to(client.get("/").inner())
+            .unwrap();
+
+        assert_eq!(Status::Ok, response.status());
+        assert_eq!(Some("image/png"), response.headers().get_one("Content-Type"));
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io,
+    process::ExitStatus,
+};
+
+use rocket::{
+    http::Status,
+    request::Request,
+    response::{self, Responder, Response},
+};
+
+/// An error which happens while creating or responding a `RawResponsePro`.
+///
+/// It implements `Responder`, so a handler can return it with `?`. Errors caused by the request are answered with a client error status, and I/O errors with `500 Internal Server Error`. When it is responded, it is logged through the `log` crate, at the error level for a server error and at the debug level otherwise.
+///
+/// More variants may be added, so a `match` needs a wildcard arm.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RawResponseError {
+    /// The file does not exist. `404 Not Found`.
+    NotFound,
+    /// The uploaded file has been persisted, but it does not exist anymore. `410 Gone`.
+    Gone,
+    /// The file cannot be accessed. `403 Forbidden`.
+    PermissionDenied,
+    /// The path is a directory. `404 Not Found`.
+    IsADirectory,
+    /// None of the requested ranges overlap the content. `416 Range Not Satisfiable`, with the `Content-Range` header.
+    RangeNotSatisfiable { complete_length: u64 },
+    /// The content type is rejected by a `ContentTypePolicy`, with its rejection status.
+    PolicyViolation(Status),
+    /// The command exited unsuccessfully before writing anything. `500 Internal Server Error`.
+    CommandFailed(ExitStatus),
+    /// Any other I/O error. `500 Internal Server Error`.
+    Io(io::Error),
+}
+
+impl RawResponseError {
+    /// The status of the response.
+    #[inline]
+    pub fn status(&self) -> Status {
+        match self {
+            RawResponseError::NotFound | RawResponseError::IsADirectory => Status::NotFound,
+            RawResponseError::Gone => Status::Gone,
+            RawResponseError::PermissionDenied => Status::Forbidden,
+            RawResponseError::RangeNotSatisfiable {
+                ..
+            } => Status::RangeNotSatisfiable,
+            RawResponseError::PolicyViolation(status) => *status,
+            RawResponseError::CommandFailed(_) | RawResponseError::Io(_) => {
+                Status::InternalServerError
+            },
+        }
+    }
+}
+
+impl From<io::Error> for RawResponseError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => RawResponseError::NotFound,
+            io::ErrorKind::PermissionDenied => RawResponseError::PermissionDenied,
+            _ => RawResponseError::Io(error),
+        }
+    }
+}
+
+impl Display for RawResponseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            RawResponseError::NotFound => f.write_str("the file does not exist"),
+            RawResponseError::Gone => f.write_str("the persisted file does not exist anymore"),
+            RawResponseError::PermissionDenied => f.write_str("the file cannot be accessed"),
+            RawResponseError::IsADirectory => f.write_str("the path is a directory"),
+            RawResponseError::RangeNotSatisfiable {
+                complete_length,
+            } => write!(f, "no requested range overlaps {} bytes", complete_length),
+            RawResponseError::PolicyViolation(status) => {
+                write!(f, "the content type is rejected with {}", status)
+            },
+            RawResponseError::CommandFailed(status) => {
+                write!(f, "the command exited with {}", status)
+            },
+            RawResponseError::Io(error) => Display::fmt(error, f),
+        }
+    }
+}
+
+impl Error for RawResponseError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RawResponseError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<'r, 'o: 'r> Responder<'r, 'o> for RawResponseError {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
+        match &self {
+            RawResponseError::CommandFailed(_) | RawResponseError::Io(_) => {
+                log::error!("rocket-raw-response: {}", self)
+            },
+            _ => log::debug!("rocket-raw-response: {}", self),
+        }
+
+        match self {
+            RawResponseError::RangeNotSatisfiable {
+                complete_length,
+            } => Response::build()
+                .status(Status::RangeNotSatisfiable)
+                .raw_header("Accept-Ranges", "bytes")
+                .raw_header("Content-Range", format!("bytes */{}", complete_length))
+                .ok(),
+            _ => Err(self.status()),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    fs::Metadata,
+    time::UNIX_EPOCH,
+};
+
+/// An entity tag, which is used as the value of the `ETag` header.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntityTag {
+    weak: bool,
+    tag:  String,
+}
+
+impl EntityTag {
+    /// Create a strong entity tag. Returns `None` if the tag contains a `"`, a whitespace or a control character.
+    #[inline]
+    pub fn strong<S: Into<String>>(tag: S) -> Option<EntityTag> {
+        EntityTag::new(false, tag.into())
+    }
+
+    /// Create a weak entity tag. Returns `None` if the tag contains a `"`, a whitespace or a control character.
+    #[inline]
+    pub fn weak<S: Into<String>>(tag: S) -> Option<EntityTag> {
+        EntityTag::new(true, tag.into())
+    }
+
+    fn new(weak: bool, tag: String) -> Option<EntityTag> {
+        if tag.bytes().all(is_etagc) {
+            Some(EntityTag {
+                weak,
+                tag,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Create a strong entity tag from the hash of the content, which is the entity tag generated for data in memory.
+    #[inline]
+    pub fn from_content(content: &[u8]) -> EntityTag {
+        EntityTag {
+            weak: false, tag: format!("{:032x}", xxhash_rust::xxh3::xxh3_128(content))
+        }
+    }
+
+    /// Whether this entity tag is weak.
+    #[inline]
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
+
+    /// The opaque tag, without the quotes and the weakness indicator.
+    #[inline]
+    pub fn tag(&self) -> &str {
+        self.tag.as_str()
+    }
+
+    /// Compare two entity tags without considering their weakness.
+    #[inline]
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.tag == other.tag
+    }
+
+    /// Compare two entity tags. They are equal only if both of them are strong.
+    #[inline]
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+}
+
+impl EntityTag {
+    /// Create an entity tag for an encoded representation of the content, e.g. `"abc-gzip"` for `"abc"`.
+    #[inline]
+    pub(crate) fn with_suffix(&self, suffix: &str) -> EntityTag {
+        EntityTag {
+            weak: self.weak, tag: format!("{}-{}", self.tag, suffix)
+        }
+    }
+
+    /// Create a weak entity tag from the size and the modification time of a file.
+    pub(crate) fn from_metadata(metadata: &Metadata) -> EntityTag {
+        let mtime = metadata
+            .modified()
+            .ok()
+            .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+            .map(|mtime| mtime.as_nanos())
+            .unwrap_or(0);
+
+        EntityTag {
+            weak: true, tag: format!("{:x}-{:x}", metadata.len(), mtime)
+        }
+    }
+}
+
+impl Display for EntityTag {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        if self.weak {
+            f.write_str("W/")?;
+        }
+
+        f.write_str("\"")?;
+        f.write_str(&self.tag)?;
+        f.write_str("\"")
+    }
+}
+
+#[inline]
+fn is_etagc(b: u8) -> bool {
+    b == 0x21 || (0x23..=0x7E).contains(&b) || b >= 0x80
+}
+
+/// Check whether an `If-Match` / `If-None-Match` style list contains a matching entity tag. `*` matches anything.
+pub(crate) fn list_matches<F: Fn(&EntityTag) -> bool>(value: &str, matches: F) -> bool {
+    let value = value.trim();
+
+    if value == "*" {
+        return true;
+    }
+
+    let mut s = value;
+
+    loop {
+        s = s.trim_start_matches([',', ' ', '\t']);
+
+        if s.is_empty() {
+            return false;
+        }
+
+        let weak = if let Some(rest) = s.strip_prefix("W/") {
+            s = rest;
+
+            true
+        } else {
+            false
+        };
+
+        let rest = match s.strip_prefix('"') {
+            Some(rest) => rest,
+            None => return false,
+        };
+
+        let end = match rest.find('"') {
+            Some(end) => end,
+            None => return false,
+        };
+
+        if let Some(etag) = EntityTag::new(weak, rest[..end].to_string()) {
+            if matches(&etag) {
+                return true;
+            }
+        }
+
+        s = &rest[end + 1..];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_matches_wildcard() {
+        assert!(list_matches("*", |_| false));
+        assert!(list_matches(" * ", |_| false));
+    }
+
+    #[test]
+    fn list_matches_strong_and_weak_tags() {
+        let strong = EntityTag::strong("v1").unwrap();
+        let weak = EntityTag::weak("v1").unwrap();
+
+        assert!(list_matches(r#""v1""#, |etag| etag.strong_eq(&strong)));
+        assert!(list_matches(r#""a", "v1""#, |etag| etag.strong_eq(&strong)));
+        assert!(!list_matches(r#"W/"v1""#, |etag| etag.strong_eq(&strong)));
+        assert!(list_matches(r#"W/"v1""#, |etag| etag.weak_eq(&strong)));
+        assert!(list_matches(r#""v1""#, |etag| etag.weak_eq(&weak)));
+        assert!(!list_matches(r#""v2", W/"v3""#, |etag| etag.weak_eq(&weak)));
+    }
+
+    #[test]
+    fn list_matches_malformed_lists() {
+        let strong = EntityTag::strong("v1").unwrap();
+
+        assert!(!list_matches("", |etag| etag.weak_eq(&strong)));
+        assert!(!list_matches("v1", |etag| etag.weak_eq(&strong)));
+        assert!(!list_matches(r#""v1"#, |etag| etag.weak_eq(&strong)));
+        assert!(!list_matches(r#"w/"v1""#, |etag| etag.weak_eq(&strong)));
+        assert!(!list_matches(r#"x, "v1""#, |etag| etag.weak_eq(&strong)));
+        assert!(list_matches(r#""v1", x"#, |etag| etag.weak_eq(&strong)));
+    }
+}
@@ -4,30 +4,88 @@
 This crate provides a response struct used for responding raw data.
 
 See `examples`.
+
+## Compression
+
+Enable the `gzip`, `brotli` or `zstd` features and call `RawResponsePro::set_compress` to encode the body according to the `Accept-Encoding` header of the request.
+
+Files which are already compressed next to the original, such as `app.js.br` and `app.js.gz`, can be served with `RawResponseBuilder::precompressed` without enabling any feature.
 */
 
+pub extern crate bytes;
 pub extern crate mime;
 
 #[macro_use]
 extern crate educe;
 
+mod accept_encoding;
+mod blocking;
+pub mod builder;
+mod cache_control;
+mod charset;
+mod command_async_reader;
+#[cfg(feature = "compression")]
+mod compression;
+mod conditional;
+mod content_disposition;
+mod content_type_policy;
+mod error;
+mod etag;
+mod mime_resolver;
+mod multipart_async_reader;
+mod precompressed;
+mod range;
+mod range_async_reader;
+mod safety_policy;
+mod sniff;
+mod std_reader;
+mod stream_async_reader;
 mod temp_file_async_reader;
+#[cfg(test)]
+mod testing;
+mod writer_async_reader;
 
 use std::{
+    borrow::Cow,
+    fs::Metadata,
+    future::Future,
     io::{self, Cursor},
     marker::Unpin,
     path::Path,
     sync::Arc,
+    time::SystemTime,
 };
 
+pub use builder::RawResponseBuilder;
+use builder::RawResponseOptions;
+use bytes::Bytes;
+pub use cache_control::{CacheControl, CacheVisibility};
+#[cfg(feature = "compression")]
+use compression::Encoding;
+use conditional::{Precondition, Validators};
+pub use content_disposition::Disposition;
+pub use content_type_policy::ContentTypePolicy;
+pub use error::RawResponseError;
+pub use etag::EntityTag;
 use mime::Mime;
+pub use mime_resolver::{DefaultMimeResolver, MimeResolver, MimeResolverState};
+use multipart_async_reader::{generate_boundary, MultipartAsyncReader};
+use precompressed::Sidecar;
+use range::Ranges;
+use range_async_reader::RangeAsyncReader;
 use rocket::{
     fs::TempFile,
+    futures::Stream,
     http::Status,
     request::Request,
     response::{self, Responder, Response},
-    tokio::{fs::File as AsyncFile, io::AsyncRead},
+    tokio::{
+        fs::File as AsyncFile,
+        io::{AsyncRead, AsyncSeek, DuplexStream},
+        process::Command,
+    },
 };
+pub use safety_policy::{ActiveContentAction, SafetyPolicy};
 use temp_file_async_reader::TempFileAsyncReader;
 
 #[derive(Educe)]
@@ -35,254 +93,1173 @@ use temp_file_async_reader::TempFileAsyncReader;
 enum RawResponseData<'o> {
     Slice(&'o [u8]),
     Vec(Vec<u8>),
+    Bytes(Bytes),
+    Shared(Arc<[u8]>),
     Reader {
         #[educe(Debug(ignore))]
         data:           Box<dyn AsyncRead + Send + Unpin + 'o>,
         content_length: Option<u64>,
     },
-    File(Arc<Path>, AsyncFile),
-    TempFile(Box<TempFile<'o>>),
+    File(Arc<Path>, AsyncFile, Box<Metadata>, Option<Vec<Sidecar>>),
+    LazyFile(Arc<Path>, bool),
+    /// An uploaded file, with its handle and metadata once it is opened if it is stored on disk.
+    TempFile(Box<TempFile<'o>>, Option<(AsyncFile, Box<Metadata>)>),
 }
 
 pub type RawResponse = RawResponsePro<'static>;
 
+/// The default maximum number of ranges a `multipart/byteranges` response can have.
+pub const DEFAULT_MAX_RANGES: usize = 16;
+
 #[derive(Debug)]
 pub struct RawResponsePro<'o> {
-    file_name:    Option<String>,
-    content_type: Option<Mime>,
-    data:         RawResponseData<'o>,
+    options: RawResponseOptions,
+    data:    RawResponseData<'o>,
 }
 
 impl<'o> RawResponsePro<'o> {
     /// Create a `RawResponse` instance from a `&'o [u8]`.
+    #[inline]
     pub fn from_slice<S: Into<String>>(
         data: &'o [u8],
         file_name: Option<S>,
         content_type: Option<Mime>,
     ) -> RawResponsePro<'o> {
-        let file_name = file_name.map(|file_name| file_name.into());
-
-        let data = RawResponseData::Slice(data);
-
-        RawResponsePro {
-            file_name,
-            content_type,
-            data,
-        }
+        RawResponsePro::slice(data).optional(file_name, content_type).build()
     }
 
     /// Create a `RawResponse` instance from a `Vec<u8>`.
+    #[inline]
     pub fn from_vec<S: Into<String>>(
         vec: Vec<u8>,
         file_name: Option<S>,
         content_type: Option<Mime>,
     ) -> RawResponsePro<'o> {
-        let file_name = file_name.map(|file_name| file_name.into());
+        RawResponsePro::vec(vec).optional(file_name, content_type).build()
+    }
 
-        let data = RawResponseData::Vec(vec);
+    /// Create a `RawResponse` instance from a `Bytes`. Cloning a `Bytes` does not copy the data, so a cached buffer can back many responses.
+    ///
+    /// Unless an entity tag is set, the whole content is hashed every time the response is responded. For a cached buffer, create an `EntityTag::from_content` once and set it with `RawResponsePro::set_etag` or `RawResponseBuilder::etag`.
+    #[inline]
+    pub fn from_bytes<S: Into<String>>(
+        bytes: Bytes,
+        file_name: Option<S>,
+        content_type: Option<Mime>,
+    ) -> RawResponsePro<'o> {
+        RawResponsePro::bytes(bytes).optional(file_name, content_type).build()
+    }
 
-        RawResponsePro {
-            file_name,
-            content_type,
-            data,
-        }
+    /// Create a `RawResponse` instance from an `Arc<[u8]>`, which is shared the way a `Bytes` is. See `RawResponsePro::from_bytes` for the entity tag of a cached buffer.
+    #[inline]
+    pub fn from_arc<S: Into<String>>(
+        data: Arc<[u8]>,
+        file_name: Option<S>,
+        content_type: Option<Mime>,
+    ) -> RawResponsePro<'o> {
+        RawResponsePro::arc(data).optional(file_name, content_type).build()
+    }
+
+    /// Create a `RawResponse` instance from a `Cow<'static, [u8]>`.
+    #[inline]
+    pub fn from_cow<S: Into<String>>(
+        data: Cow<'static, [u8]>,
+        file_name: Option<S>,
+        content_type: Option<Mime>,
+    ) -> RawResponsePro<'o> {
+        RawResponsePro::cow(data).optional(file_name, content_type).build()
     }
 
     /// Create a `RawResponse` instance from a reader.
+    #[inline]
     pub fn from_reader<R: AsyncRead + Send + Unpin + 'o, S: Into<String>>(
         reader: R,
         file_name: Option<S>,
         content_type: Option<Mime>,
         content_length: Option<u64>,
     ) -> RawResponsePro<'o> {
-        let file_name = file_name.map(|file_name| file_name.into());
+        let builder = RawResponsePro::reader(reader).optional(file_name, content_type);
 
-        let data = RawResponseData::Reader {
-            data: Box::new(reader),
-            content_length,
-        };
+        match content_length {
+            Some(content_length) => builder.content_length(content_length).build(),
+            None => builder.build(),
+        }
+    }
 
-        RawResponsePro {
-            file_name,
-            content_type,
-            data,
+    /// Create a `RawResponse` instance from a stream of chunks.
+    #[inline]
+    pub fn from_stream<T: Stream<Item = Result<Bytes, io::Error>> + Send + 'o, S: Into<String>>(
+        stream: T,
+        file_name: Option<S>,
+        content_type: Option<Mime>,
+        content_length: Option<u64>,
+    ) -> RawResponsePro<'o> {
+        let builder = RawResponsePro::stream(stream).optional(file_name, content_type);
+
+        match content_length {
+            Some(content_length) => builder.content_length(content_length).build(),
+            None => builder.build(),
         }
     }
 
-    /// Create a `RawResponse` instance from a path of a file.
-    pub async fn from_file<P: Into<Arc<Path>>, S: Into<String>>(
-        path: P,
+    /// Create a `RawResponse` instance from a blocking reader.
+    #[inline]
+    pub fn from_std_reader<R: std::io::Read + Send + 'static, S: Into<String>>(
+        reader: R,
+        file_name: Option<S>,
+        content_type: Option<Mime>,
+        content_length: Option<u64>,
+    ) -> RawResponsePro<'o> {
+        let builder = RawResponsePro::std_reader(reader).optional(file_name, content_type);
+
+        match content_length {
+            Some(content_length) => builder.content_length(content_length).build(),
+            None => builder.build(),
+        }
+    }
+
+    /// Create a `RawResponse` instance from a producer writing the body to a pipe.
+    #[inline]
+    pub fn from_writer<F, Fut, S: Into<String>>(
+        producer: F,
         file_name: Option<S>,
         content_type: Option<Mime>,
-    ) -> Result<RawResponsePro<'o>, io::Error> {
-        let path = path.into();
+        content_length: Option<u64>,
+    ) -> RawResponsePro<'o>
+    where
+        F: FnOnce(DuplexStream) -> Fut + Send + 'static,
+        Fut: Future<Output = io::Result<()>> + Send + 'static, {
+        let builder = RawResponsePro::writer(producer).optional(file_name, content_type);
 
-        let file = AsyncFile::open(path.as_ref()).await?;
+        match content_length {
+            Some(content_length) => builder.content_length(content_length).build(),
+            None => builder.build(),
+        }
+    }
 
-        let file_name = file_name.map(|file_name| file_name.into());
+    /// Create a `RawResponse` instance from the standard output of a command.
+    #[inline]
+    pub async fn from_command<S: Into<String>>(
+        command: Command,
+        file_name: Option<S>,
+        content_type: Option<Mime>,
+    ) -> Result<RawResponsePro<'o>, RawResponseError> {
+        RawResponsePro::command(command).optional(file_name, content_type).build().await
+    }
 
-        let data = RawResponseData::File(path, file);
+    /// Create a `RawResponse` instance from a path of a file.
+    #[inline]
+    pub async fn from_file<P: Into<Arc<Path>>, S: Into<String>>(
+        path: P,
+        file_name: Option<S>,
+        content_type: Option<Mime>,
+    ) -> Result<RawResponsePro<'o>, RawResponseError> {
+        RawResponsePro::file(path).optional(file_name, content_type).build().await
+    }
 
-        Ok(RawResponsePro {
-            file_name,
-            content_type,
-            data,
-        })
+    /// Create a `RawResponse` instance from a path of a file which is opened when the response is responded.
+    #[inline]
+    pub fn from_lazy_file<P: Into<Arc<Path>>, S: Into<String>>(
+        path: P,
+        file_name: Option<S>,
+        content_type: Option<Mime>,
+    ) -> RawResponsePro<'o> {
+        RawResponsePro::lazy_file(path).optional(file_name, content_type).build()
     }
 
     /// Create a `RawResponse` instance from a `TempFile`.
+    #[inline]
     pub fn from_temp_file<S: Into<String>>(
         temp_file: TempFile<'o>,
         file_name: Option<S>,
         content_type: Option<Mime>,
     ) -> RawResponsePro<'o> {
-        let file_name = file_name.map(|file_name| file_name.into());
+        RawResponsePro::temp_file(temp_file).optional(file_name, content_type).build()
+    }
+}
 
-        let data = RawResponseData::TempFile(Box::new(temp_file));
+impl<'o> RawResponsePro<'o> {
+    /// Set the disposition type of the `Content-Disposition` header. The default value is `Disposition::Inline`.
+    ///
+    /// The header carries the file name given to the constructor. If no file name is given, the name of the file, or the name of the uploaded temporary file, is used instead. An empty file name means no file name at all.
+    #[inline]
+    pub fn set_disposition(&mut self, disposition: Disposition) {
+        self.options.disposition = disposition;
+    }
 
-        RawResponsePro {
-            file_name,
-            content_type,
-            data,
-        }
+    /// Set the maximum number of ranges a request can ask for. The default value is `DEFAULT_MAX_RANGES`.
+    ///
+    /// Overlapping and adjacent ranges are merged before counting, and a request asking for more ranges gets the full content instead. `0` disables range requests and `1` disables `multipart/byteranges` responses.
+    #[inline]
+    pub fn set_max_ranges(&mut self, max_ranges: usize) {
+        self.options.max_ranges = max_ranges;
+    }
+
+    /// Set the entity tag of the response, replacing the generated one.
+    ///
+    /// Slices, vectors and buffered temporary files get a strong entity tag hashed from their content, and files get a weak entity tag derived from their size and modification time. A reader has no entity tag unless one is set.
+    #[inline]
+    pub fn set_etag(&mut self, etag: EntityTag) {
+        self.options.etag = Some(etag);
+    }
+
+    /// Set the time the content was last modified, replacing the modification time of a file.
+    #[inline]
+    pub fn set_last_modified(&mut self, last_modified: SystemTime) {
+        self.options.last_modified = Some(last_modified);
+    }
+
+    /// Set the caching policy, which is sent as the `Cache-Control` and `Expires` headers, including in `304 Not Modified` responses.
+    #[inline]
+    pub fn set_cache_control(&mut self, cache_control: CacheControl) {
+        self.options.cache_control = Some(cache_control);
+    }
+
+    /// Set the policy for serving untrusted content, such as user uploads. See `SafetyPolicy`.
+    #[inline]
+    pub fn set_safety_policy(&mut self, safety_policy: SafetyPolicy) {
+        self.options.safety_policy = Some(safety_policy);
+    }
+
+    /// Set the policy deciding which content types may be responded. It is checked against the content type after it is guessed, sniffed or set. See `ContentTypePolicy`.
+    #[inline]
+    pub fn set_content_type_policy(&mut self, content_type_policy: ContentTypePolicy) {
+        self.options.content_type_policy = Some(content_type_policy);
+    }
+
+    /// Set the `MimeResolver` used to guess the content type from the file name, replacing the one registered as `MimeResolverState` and the `DefaultMimeResolver`.
+    #[inline]
+    pub fn set_mime_resolver<R: MimeResolver + 'static>(&mut self, mime_resolver: R) {
+        self.options.mime_resolver = Some(Arc::new(mime_resolver));
+    }
+
+    /// Set whether the body is compressed with gzip, brotli or zstd, whichever is enabled and preferred by the `Accept-Encoding` header of the request. The default value is `false`.
+    ///
+    /// Already compressed content types, such as images, videos and archives, are sent as they are. A compressed body has no `Content-Length` header and ignores the `Range` header, and a request with a `Range` header always gets the original body.
+    #[cfg(feature = "compression")]
+    #[inline]
+    pub fn set_compress(&mut self, compress: bool) {
+        self.options.compress = compress;
     }
 }
 
-macro_rules! file_name {
-    ($s:expr, $res:expr) => {
-        if let Some(file_name) = $s.file_name {
-            if file_name.is_empty() {
-                $res.raw_header("Content-Disposition", "inline");
-            } else {
-                let mut v = String::from("inline; filename*=UTF-8''");
+/// A reader which can also seek.
+trait AsyncReadSeek: AsyncRead + AsyncSeek {}
 
-                url_escape::encode_component_to_string(file_name, &mut v);
+impl<T: AsyncRead + AsyncSeek + ?Sized> AsyncReadSeek for T {}
 
-                $res.raw_header("Content-Disposition", v);
-            }
-        }
-    };
+/// The body of a response, after the data is resolved.
+enum Body<'o> {
+    /// A body whose length is known and whose ranges can be read.
+    Seekable(Box<dyn AsyncReadSeek + Send + Unpin + 'o>, u64),
+    /// A body which can only be read from the start, with an optional length.
+    Stream(Box<dyn AsyncRead + Send + Unpin + 'o>, Option<u64>),
+}
+
+/// The file name of an uploaded file, which is the name sanitized by Rocket with the extension of the raw file name sent by the client, if the extension is safe.
+fn upload_file_name(file: &TempFile<'_>) -> Option<String> {
+    let name = file.name()?;
+
+    let raw_name = file.raw_name()?.dangerous_unsafe_unsanitized_raw().as_str();
+    let raw_name = raw_name.rsplit(['/', '\\']).next().unwrap_or(raw_name);
+
+    let extension = raw_name.rsplit_once('.').map(|(_, extension)| extension).filter(|extension| {
+        !extension.is_empty()
+            && extension.len() <= 16
+            && extension.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    });
+
+    match extension {
+        Some(extension) => Some(format!("{}.{}", name, extension)),
+        None => Some(name.to_string()),
+    }
 }
 
-macro_rules! content_type {
-    ($s:expr, $res:expr) => {
-        if let Some(content_type) = $s.content_type {
-            $res.raw_header("Content-Type", content_type.to_string());
+/// What is going to be responded, after the data is resolved.
+struct Representation<'o> {
+    file_name:              Option<String>,
+    content_type:           Option<String>,
+    /// The content type resolved from the name of an uploaded file. A `ContentTypePolicy` checks it too, because a client can declare any content type.
+    file_name_content_type: Option<String>,
+    content_encoding:       Option<&'static str>,
+    validators:             Validators,
+    body:                   Body<'o>,
+}
+
+impl<'o> Representation<'o> {
+    /// A representation of data in memory, whose entity tag is computed from the content unless it is given.
+    fn from_buffer<T: AsRef<[u8]> + Send + Unpin + 'o>(
+        data: T,
+        file_name: Option<String>,
+        content_type: Option<Mime>,
+        etag: Option<EntityTag>,
+        last_modified: Option<SystemTime>,
+    ) -> Representation<'o> {
+        let validators = Validators::new(
+            etag.or_else(|| Some(EntityTag::from_content(data.as_ref()))),
+            last_modified,
+        );
+
+        let len = data.as_ref().len() as u64;
+
+        Representation {
+            file_name,
+            content_type: content_type.map(|content_type| content_type.to_string()),
+            file_name_content_type: None,
+            content_encoding: None,
+            validators,
+            body: Body::Seekable(Box::new(Cursor::new(data)), len),
         }
-    };
+    }
 }
 
 impl<'r, 'o: 'r> Responder<'r, 'o> for RawResponsePro<'o> {
-    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'o> {
-        let mut response = Response::build();
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'o> {
+        let mut options = self.options;
 
-        match self.data {
-            RawResponseData::Slice(data) => {
-                file_name!(self, response);
-                content_type!(self, response);
+        let managed_mime_resolver =
+            request.rocket().state::<MimeResolverState>().map(|state| state.resolver());
 
-                response.sized_body(data.len(), Cursor::new(data));
+        let data = match self.data {
+            RawResponseData::LazyFile(path, precompressed) => {
+                match blocking::block_in_place(|| {
+                    builder::open_file(path, precompressed, &mut options, managed_mime_resolver)
+                }) {
+                    Ok(data) => data,
+                    Err(error) => return error.respond_to(request),
+                }
             },
-            RawResponseData::Vec(data) => {
-                file_name!(self, response);
-                content_type!(self, response);
-
-                response.sized_body(data.len(), Cursor::new(data));
+            RawResponseData::TempFile(file, None) => match file.as_ref() {
+                TempFile::File {
+                    path, ..
+                } => match blocking::block_in_place(|| {
+                    blocking::open_temp_file(AsRef::<Path>::as_ref(path), path.is_right())
+                }) {
+                    Ok((async_file, metadata)) => RawResponseData::TempFile(
+                        file,
+                        Some((AsyncFile::from_std(async_file), Box::new(metadata))),
+                    ),
+                    Err(error) => return error.respond_to(request),
+                },
+                TempFile::Buffered {
+                    ..
+                } => RawResponseData::TempFile(file, None),
             },
+            data => data,
+        };
+
+        let mime_resolver: &dyn MimeResolver =
+            match options.mime_resolver.as_deref().or(managed_mime_resolver) {
+                Some(mime_resolver) => mime_resolver,
+                None => &DefaultMimeResolver,
+            };
+
+        let guess_content_type = |file_name: &str| {
+            mime_resolver.resolve(file_name).map(|content_type| content_type.to_string())
+        };
+
+        let mut vary_accept_encoding = false;
+
+        let mut representation = match data {
+            RawResponseData::Slice(data) => Representation::from_buffer(
+                data,
+                options.file_name,
+                options.content_type.or(options.sniffed_content_type),
+                options.etag,
+                options.last_modified,
+            ),
+            RawResponseData::Vec(data) => Representation::from_buffer(
+                data,
+                options.file_name,
+                options.content_type.or(options.sniffed_content_type),
+                options.etag,
+                options.last_modified,
+            ),
+            RawResponseData::Bytes(data) => Representation::from_buffer(
+                data,
+                options.file_name,
+                options.content_type.or(options.sniffed_content_type),
+                options.etag,
+                options.last_modified,
+            ),
+            RawResponseData::Shared(data) => Representation::from_buffer(
+                data,
+                options.file_name,
+                options.content_type.or(options.sniffed_content_type),
+                options.etag,
+                options.last_modified,
+            ),
             RawResponseData::Reader {
                 data,
                 content_length,
-            } => {
-                file_name!(self, response);
-                content_type!(self, response);
+            } => Representation {
+                file_name:              options.file_name,
+                content_type:           options
+                    .content_type
+                    .or(options.sniffed_content_type)
+                    .map(|content_type| content_type.to_string()),
+                file_name_content_type: None,
+                content_encoding:       None,
+                validators:             Validators::new(options.etag, options.last_modified),
+                body:                   Body::Stream(data, content_length),
+            },
+            RawResponseData::File(path, file, metadata, sidecars) => {
+                let file_name = options.file_name.or_else(|| {
+                    path.file_name().map(|file_name| file_name.to_string_lossy().into_owned())
+                });
 
-                if let Some(content_length) = content_length {
-                    response.raw_header("Content-Length", content_length.to_string());
-                }
+                let content_type = match options.content_type {
+                    Some(content_type) => Some(content_type.to_string()),
+                    None => path
+                        .file_name()
+                        .and_then(|file_name| file_name.to_str())
+                        .and_then(&guess_content_type)
+                        .or_else(|| {
+                            options
+                                .sniffed_content_type
+                                .map(|content_type| content_type.to_string())
+                        }),
+                };
+
+                let sidecar = match sidecars {
+                    Some(sidecars) => {
+                        vary_accept_encoding = true;
+
+                        precompressed::select(sidecars, request)
+                    },
+                    None => None,
+                };
 
-                response.streamed_body(data);
+                match sidecar {
+                    Some(sidecar) => {
+                        let coding = sidecar.coding;
+
+                        let validators = Validators::new(
+                            options
+                                .etag
+                                .or_else(|| Some(EntityTag::from_metadata(&sidecar.metadata)))
+                                .map(|etag| etag.with_suffix(coding)),
+                            options.last_modified.or_else(|| sidecar.metadata.modified().ok()),
+                        );
+
+                        Representation {
+                            file_name,
+                            content_type,
+                            file_name_content_type: None,
+                            content_encoding: Some(coding),
+                            validators,
+                            body: Body::Seekable(Box::new(sidecar.file), sidecar.metadata.len()),
+                        }
+                    },
+                    None => {
+                        let validators = Validators::new(
+                            options.etag.or_else(|| Some(EntityTag::from_metadata(&metadata))),
+                            options.last_modified.or_else(|| metadata.modified().ok()),
+                        );
+
+                        Representation {
+                            file_name,
+                            content_type,
+                            file_name_content_type: None,
+                            content_encoding: None,
+                            validators,
+                            body: Body::Seekable(Box::new(file), metadata.len()),
+                        }
+                    },
+                }
             },
-            RawResponseData::File(path, file) => {
-                if let Some(file_name) = self.file_name {
-                    if file_name.is_empty() {
-                        response.raw_header("Content-Disposition", "inline");
-                    } else {
-                        let mut v = String::from("inline; filename*=UTF-8''");
-
-                        url_escape::encode_component_to_string(file_name, &mut v);
-
-                        response.raw_header("Content-Disposition", v);
-                    }
-                } else if let Some(file_name) =
-                    path.file_name().map(|file_name| file_name.to_string_lossy())
-                {
-                    let mut v = String::from("inline; filename*=UTF-8''");
+            RawResponseData::LazyFile(..) => unreachable!("the lazy file has been opened"),
+            RawResponseData::TempFile(file, opened) => {
+                let metadata = opened.as_ref().map(|(_, metadata)| metadata.as_ref());
 
-                    url_escape::encode_component_to_string(file_name, &mut v);
+                let validators = Validators::new(
+                    options.etag.or_else(|| match file.as_ref() {
+                        TempFile::Buffered {
+                            content,
+                        } => Some(EntityTag::from_content(content)),
+                        TempFile::File {
+                            ..
+                        } => metadata.map(EntityTag::from_metadata),
+                    }),
+                    options
+                        .last_modified
+                        .or_else(|| metadata.and_then(|metadata| metadata.modified().ok())),
+                );
+
+                let upload_file_name = upload_file_name(&file);
+
+                // a client often sends `application/octet-stream` for any file, so the extension is more useful
+                let client_content_type = file
+                    .content_type()
+                    .filter(|content_type| !content_type.is_binary() && !content_type.is_any());
 
-                    response.raw_header("Content-Disposition", v);
+                let file_name_content_type =
+                    upload_file_name.as_deref().and_then(&guess_content_type);
+
+                let content_type = if let Some(content_type) = options.content_type {
+                    Some(content_type.to_string())
+                } else if let Some(content_type) = client_content_type {
+                    Some(content_type.to_string())
                 } else {
-                    response.raw_header("Content-Disposition", "inline");
+                    file_name_content_type
+                        .clone()
+                        .or_else(|| {
+                            file.content_type().map(|content_type| content_type.to_string())
+                        })
+                        .or_else(|| {
+                            options
+                                .sniffed_content_type
+                                .map(|content_type| content_type.to_string())
+                        })
+                };
+
+                let file_name = options.file_name.or(upload_file_name);
+
+                let (async_file, len) = match opened {
+                    Some((async_file, metadata)) => (Some(async_file), metadata.len()),
+                    None => (None, file.len()),
+                };
+
+                let reader = TempFileAsyncReader::new(file, async_file);
+
+                Representation {
+                    file_name,
+                    content_type,
+                    file_name_content_type,
+                    content_encoding: None,
+                    validators,
+                    body: Body::Seekable(Box::new(reader), len),
                 }
+            },
+        };
 
-                if let Some(content_type) = self.content_type {
-                    response.raw_header("Content-Type", content_type.to_string());
-                } else if let Some(extension) = path.extension() {
-                    if let Some(extension) = extension.to_str() {
-                        let content_type = mime_guess::from_ext(extension).first_or_octet_stream();
+        #[cfg(feature = "compression")]
+        if options.compress {
+            vary_accept_encoding = true;
 
-                        response.raw_header("Content-Type", content_type.to_string());
-                    }
+            if representation.content_encoding.is_none() {
+                if let Some(encoding) =
+                    Encoding::from_request(request, representation.content_type.as_deref())
+                {
+                    let validators = &mut representation.validators;
+
+                    validators.etag =
+                        validators.etag.take().map(|etag| etag.with_suffix(encoding.as_str()));
+
+                    representation.content_encoding = Some(encoding.as_str());
+
+                    representation.body = match representation.body {
+                        Body::Seekable(reader, _) => Body::Stream(encoding.encode(reader), None),
+                        Body::Stream(reader, _) => Body::Stream(encoding.encode(reader), None),
+                    };
                 }
+            }
+        }
 
-                response.sized_body(None, file);
-            },
-            RawResponseData::TempFile(file) => {
-                if let Some(file_name) = self.file_name {
-                    if file_name.is_empty() {
-                        response.raw_header("Content-Disposition", "inline");
-                    } else {
-                        let mut v = String::from("inline; filename*=UTF-8''");
-
-                        url_escape::encode_component_to_string(file_name, &mut v);
-
-                        response.raw_header("Content-Disposition", v);
-                    }
-                } else if let Some(file_name) = file.name() {
-                    if file_name.is_empty() {
-                        response.raw_header("Content-Disposition", "inline");
-                    } else {
-                        let mut v = String::from("attachment; filename*=UTF-8''");
-
-                        url_escape::encode_component_to_string(file_name, &mut v);
-
-                        response.raw_header("Content-Disposition", v);
-                    }
+        if let Some(charset) = options.charset {
+            representation.content_type = representation
+                .content_type
+                .map(|content_type| charset::with_charset(content_type, charset));
+        }
+
+        if let Some(content_type_policy) = options.content_type_policy.as_ref() {
+            let checked = content_type_policy
+                .check(representation.content_type.as_deref())
+                .and_then(|_| match representation.file_name_content_type.as_deref() {
+                    Some(content_type) => content_type_policy.check(Some(content_type)),
+                    None => Ok(()),
+                });
+
+            if let Err(status) = checked {
+                return RawResponseError::PolicyViolation(status).respond_to(request);
+            }
+        }
+
+        let mut disposition = options.disposition;
+        let mut sandbox = false;
+
+        if let Some(safety_policy) = options.safety_policy.as_ref() {
+            let (active, action) = safety_policy.check(representation.content_type.as_deref());
+
+            sandbox = active;
+
+            match action {
+                Some(ActiveContentAction::Attachment) => disposition = Disposition::Attachment,
+                Some(ActiveContentAction::OctetStream) => {
+                    representation.content_type = Some(mime::APPLICATION_OCTET_STREAM.to_string())
+                },
+                None => (),
+            }
+        }
+
+        let Representation {
+            file_name,
+            content_type,
+            content_encoding,
+            validators,
+            body,
+            ..
+        } = representation;
+
+        let mut response = Response::build();
+
+        if let Some(cache_control) =
+            options.cache_control.filter(|cache_control| !cache_control.is_empty())
+        {
+            response.raw_header("Cache-Control", cache_control.to_string());
+
+            if let Some(expires) = cache_control.expires() {
+                response.raw_header("Expires", conditional::fmt_http_date(expires));
+            }
+        }
+
+        if vary_accept_encoding {
+            response.raw_header("Vary", "Accept-Encoding");
+        }
+
+        let precondition = Precondition::from_request(request, &validators);
+
+        if precondition == Precondition::Failed {
+            return Response::build().status(Status::PreconditionFailed).ok();
+        }
+
+        if let Some(etag) = validators.etag.as_ref() {
+            response.raw_header("ETag", etag.to_string());
+        }
+
+        if let Some(last_modified) = validators.last_modified {
+            response.raw_header("Last-Modified", conditional::fmt_http_date(last_modified));
+        }
+
+        if precondition == Precondition::NotModified {
+            return response.status(Status::NotModified).ok();
+        }
+
+        if options.safety_policy.is_some() {
+            response.raw_header("X-Content-Type-Options", "nosniff");
+        }
+
+        if sandbox {
+            response.raw_header("Content-Security-Policy", "sandbox");
+        }
+
+        if let Some(v) = disposition.to_header_value(file_name.as_deref()) {
+            response.raw_header("Content-Disposition", v);
+        }
+
+        if let Some(content_type) = content_type.as_ref() {
+            response.raw_header("Content-Type", content_type.clone());
+        }
+
+        if let Some(content_encoding) = content_encoding {
+            response.raw_header("Content-Encoding", content_encoding);
+        }
+
+        match body {
+            Body::Seekable(body, len) => {
+                if options.max_ranges > 0 {
+                    response.raw_header("Accept-Ranges", "bytes");
                 } else {
-                    response.raw_header("Content-Disposition", "inline");
+                    response.raw_header("Accept-Ranges", "none");
                 }
 
-                if let Some(content_type) = self.content_type {
-                    response.raw_header("Content-Type", content_type.to_string());
-                } else if let Some(content_type) = file.content_type() {
-                    response.raw_header("Content-Type", content_type.to_string());
-                } else if let Some(extension) = file.name().map(Path::new).and_then(Path::extension)
-                {
-                    if let Some(extension) = extension.to_str() {
-                        let content_type = mime_guess::from_ext(extension).first_or_octet_stream();
+                match Ranges::from_request(request, len, options.max_ranges, &validators) {
+                    Ranges::Full => {
+                        response.sized_body(len as usize, body);
+                    },
+                    Ranges::Partial(range) => {
+                        response.status(Status::PartialContent);
+                        response.raw_header("Content-Range", range.content_range(len));
+                        response.raw_header("Content-Length", range.len().to_string());
 
-                        response.raw_header("Content-Type", content_type.to_string());
-                    }
-                }
+                        response.streamed_body(RangeAsyncReader::new(
+                            body,
+                            range.start,
+                            range.len(),
+                        ));
+                    },
+                    Ranges::Multiple(ranges) => {
+                        let boundary = generate_boundary();
 
-                response.raw_header("Content-Length", file.len().to_string());
+                        let body = MultipartAsyncReader::new(
+                            body,
+                            &boundary,
+                            &ranges,
+                            len,
+                            content_type.as_deref(),
+                        );
 
-                response.streamed_body(
-                    TempFileAsyncReader::from(file).map_err(|_| Status::InternalServerError)?,
-                );
+                        response.status(Status::PartialContent);
+                        response.raw_header(
+                            "Content-Type",
+                            format!("multipart/byteranges; boundary={}", boundary),
+                        );
+                        response.raw_header("Content-Length", body.len().to_string());
+
+                        response.streamed_body(body);
+                    },
+                    Ranges::Unsatisfiable => {
+                        return RawResponseError::RangeNotSatisfiable {
+                            complete_length: len
+                        }
+                        .respond_to(request);
+                    },
+                }
+            },
+            Body::Stream(body, content_length) => {
+                if let Some(content_length) = content_length {
+                    response.raw_header("Content-Length", content_length.to_string());
+                }
+
+                response.streamed_body(body);
             },
         }
 
         response.ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, time::Duration};
+
+    use rocket::{fs::FileName, http::Header, Either};
+
+    use super::*;
+    use crate::testing::{client, client_of, respond};
+
+    #[rocket::async_test]
+    async fn respond_range() {
+        let client = client().await;
+
+        let mut response = respond(
+            client.get("/"),
+            &[("Range", "bytes=2-5")],
+            RawResponse::slice(b"0123456789").build(),
+        );
+
+        assert_eq!(Status::PartialContent, response.status());
+        assert_eq!(Some("bytes"), response.headers().get_one("Accept-Ranges"));
+        assert_eq!(Some("bytes 2-5/10"), response.headers().get_one("Content-Range"));
+        assert_eq!(Some("4"), response.headers().get_one("Content-Length"));
+        assert_eq!(b"2345", response.body_mut().to_bytes().await.unwrap().as_slice());
+    }
+
+    #[rocket::async_test]
+    async fn respond_range_not_satisfiable() {
+        let client = client().await;
+
+        let mut response = respond(
+            client.get("/"),
+            &[("Range", "bytes=20-30")],
+            RawResponse::slice(b"0123456789").build(),
+        );
+
+        assert_eq!(Status::RangeNotSatisfiable, response.status());
+        assert_eq!(Some("bytes */10"), response.headers().get_one("Content-Range"));
+        assert!(response.body_mut().to_bytes().await.unwrap().is_empty());
+    }
+
+    #[rocket::async_test]
+    async fn respond_range_disabled() {
+        let client = client().await;
+
+        let mut response = respond(
+            client.get("/"),
+            &[("Range", "bytes=2-5")],
+            RawResponse::slice(b"0123456789").max_ranges(0).build(),
+        );
+
+        assert_eq!(Status::Ok, response.status());
+        assert_eq!(Some("none"), response.headers().get_one("Accept-Ranges"));
+        assert_eq!(None, response.headers().get_one("Content-Range"));
+        assert_eq!(b"0123456789", response.body_mut().to_bytes().await.unwrap().as_slice());
+    }
+
+    #[rocket::async_test]
+    async fn respond_not_modified() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.js");
+
+        std::fs::write(&path, "alert(1);").unwrap();
+
+        let build = || {
+            RawResponse::file(path.as_path())
+                .precompressed(true)
+                .cache_control(CacheControl::REVALIDATE)
+                .build()
+        };
+
+        let response = respond(client.get("/"), &[], build().await.unwrap());
+
+        let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+        let mut request = client.get("/").header(Header::new("If-None-Match", etag.clone()));
+        request = request.header(Header::new("Range", "bytes=0-0"));
+
+        let mut response = respond(request, &[], build().await.unwrap());
+
+        assert_eq!(Status::NotModified, response.status());
+        assert_eq!(Some(etag.as_str()), response.headers().get_one("ETag"));
+        assert_eq!(Some("max-age=0, must-revalidate"), response.headers().get_one("Cache-Control"));
+        assert_eq!(Some("Accept-Encoding"), response.headers().get_one("Vary"));
+        assert_eq!(None, response.headers().get_one("Content-Type"));
+        assert_eq!(None, response.headers().get_one("Content-Range"));
+        assert!(response.body_mut().to_bytes().await.unwrap().is_empty());
+    }
+
+    #[rocket::async_test]
+    async fn respond_file_modified_before_epoch() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("old.txt");
+
+        let file = std::fs::File::create(&path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH - Duration::from_secs(10 * 365 * 24 * 60 * 60))
+            .unwrap();
+
+        let response = respond(
+            client.get("/"),
+            &[("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT")],
+            RawResponse::file(path).build().await.unwrap(),
+        );
+
+        assert_eq!(Status::Ok, response.status());
+        assert_eq!(None, response.headers().get_one("Last-Modified"));
+        assert!(response.headers().get_one("ETag").is_some());
+    }
+
+    #[rocket::async_test]
+    async fn respond_cache_control() {
+        let client = client().await;
+
+        let cache_control = CacheControl::new().public().max_age(Duration::from_secs(600));
+
+        let build = || RawResponse::slice(b"hello").cache_control(cache_control).build();
+
+        let response = respond(client.get("/"), &[], build());
+
+        assert_eq!(Some("public, max-age=600"), response.headers().get_one("Cache-Control"));
+        assert!(response.headers().get_one("Expires").is_some());
+
+        let etag = response.headers().get_one("ETag").unwrap().to_string();
+        let request = client.get("/").header(Header::new("If-None-Match", etag));
+
+        let response = respond(request, &[], build());
+
+        assert_eq!(Status::NotModified, response.status());
+        assert_eq!(Some("public, max-age=600"), response.headers().get_one("Cache-Control"));
+        assert!(response.headers().get_one("Expires").is_some());
+
+        let response = respond(
+            client.get("/"),
+            &[],
+            RawResponse::slice(b"hello").cache_control(CacheControl::new()).build(),
+        );
+
+        assert_eq!(None, response.headers().get_one("Cache-Control"));
+        assert_eq!(None, response.headers().get_one("Expires"));
+    }
+
+    #[rocket::async_test]
+    async fn respond_far_future_expires() {
+        let client = client().await;
+
+        for max_age in [Duration::from_secs(400_000_000_000), Duration::MAX] {
+            let response = respond(
+                client.get("/"),
+                &[],
+                RawResponse::slice(b"hello")
+                    .cache_control(CacheControl::new().max_age(max_age))
+                    .build(),
+            );
+
+            assert_eq!(Status::Ok, response.status());
+            assert_eq!(
+                Some("Fri, 31 Dec 9999 23:59:59 GMT"),
+                response.headers().get_one("Expires")
+            );
+        }
+    }
+
+    #[rocket::async_test]
+    async fn respond_sniffed_reader() {
+        let client = client().await;
+
+        let build = |sniff| {
+            RawResponse::reader(Cursor::new(b"\x89PNG\r\n\x1a\n".to_vec()))
+                .sniff(sniff)
+                .sniff_content_type()
+        };
+
+        let mut response = respond(client.get("/"), &[], build(true).await.unwrap().build());
+
+        assert_eq!(Some("image/png"), response.headers().get_one("Content-Type"));
+        assert_eq!(b"\x89PNG\r\n\x1a\n", response.body_mut().to_bytes().await.unwrap().as_slice());
+
+        let mut response = respond(client.get("/"), &[], build(false).await.unwrap().build());
+
+        assert_eq!(None, response.headers().get_one("Content-Type"));
+        assert_eq!(b"\x89PNG\r\n\x1a\n", response.body_mut().to_bytes().await.unwrap().as_slice());
+    }
+
+    #[rocket::async_test]
+    async fn respond_file_charset() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let files: [(&str, &[u8], &str); 3] = [
+            ("a.csv", b"a,b\n", "text/csv; charset=utf-8"),
+            ("a.txt", b"\xff\xfea\x00", "text/plain; charset=utf-16"),
+            ("a.png", b"a,b\n", "image/png"),
+        ];
+
+        for (file_name, content, content_type) in files {
+            let path = dir.path().join(file_name);
+
+            std::fs::write(&path, content).unwrap();
+
+            let mut response =
+                respond(client.get("/"), &[], RawResponse::file(path).build().await.unwrap());
+
+            assert_eq!(Some(content_type), response.headers().get_one("Content-Type"));
+            assert_eq!(content, response.body_mut().to_bytes().await.unwrap().as_slice());
+        }
+    }
+
+    #[rocket::async_test]
+    async fn respond_lazy_file() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+
+        std::fs::write(&path, "hello").unwrap();
+
+        let mut response =
+            respond(client.get("/"), &[], RawResponse::lazy_file(path.as_path()).build());
+
+        assert_eq!(Status::Ok, response.status());
+        assert_eq!(Some("text/plain; charset=utf-8"), response.headers().get_one("Content-Type"));
+        assert!(response.headers().get_one("Last-Modified").is_some());
+        assert_eq!(b"hello", response.body_mut().to_bytes().await.unwrap().as_slice());
+
+        for path in [dir.path().join("missing.txt"), dir.path().to_path_buf()] {
+            let response = RawResponse::lazy_file(path).build().respond_to(client.get("/").inner());
+
+            assert_eq!(Some(Status::NotFound), response.err());
+        }
+
+        let error = RawResponse::file(dir.path()).build().await.unwrap_err();
+
+        assert!(matches!(error, RawResponseError::IsADirectory));
+    }
+
+    struct NoteMimeResolver;
+
+    impl MimeResolver for NoteMimeResolver {
+        fn resolve(&self, file_name: &str) -> Option<Mime> {
+            if file_name.ends_with(".note") {
+                return Some(mime::TEXT_PLAIN);
+            }
+
+            DefaultMimeResolver.resolve(file_name)
+        }
+    }
+
+    #[rocket::async_test]
+    async fn respond_managed_mime_resolver() {
+        let client =
+            client_of(rocket::build().manage(MimeResolverState::new(NoteMimeResolver))).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.note");
+
+        std::fs::write(&path, "hello").unwrap();
+
+        let response =
+            respond(client.get("/"), &[], RawResponse::lazy_file(path.as_path()).build());
+
+        assert_eq!(Some("text/plain; charset=utf-8"), response.headers().get_one("Content-Type"));
+
+        let response =
+            respond(client.get("/"), &[], RawResponse::file(path.as_path()).build().await.unwrap());
+
+        assert_eq!(Some("text/plain"), response.headers().get_one("Content-Type"));
+
+        let response = respond(
+            client.get("/"),
+            &[],
+            RawResponse::file(path.as_path())
+                .mime_resolver(DefaultMimeResolver)
+                .build()
+                .await
+                .unwrap(),
+        );
+
+        assert_eq!(Some("application/octet-stream"), response.headers().get_one("Content-Type"));
+    }
+
+    #[rocket::async_test]
+    async fn respond_mime_resolver() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.note");
+
+        std::fs::write(&path, "hello").unwrap();
+
+        let response = respond(
+            client.get("/"),
+            &[],
+            RawResponse::file(path.as_path())
+                .mime_resolver(NoteMimeResolver)
+                .build()
+                .await
+                .unwrap(),
+        );
+
+        assert_eq!(Some("text/plain; charset=utf-8"), response.headers().get_one("Content-Type"));
+
+        let response =
+            respond(client.get("/"), &[], RawResponse::file(path.as_path()).build().await.unwrap());
+
+        assert_eq!(Some("application/octet-stream"), response.headers().get_one("Content-Type"));
+    }
+
+    #[rocket::async_test]
+    async fn respond_shared_buffer_etag() {
+        let client = client().await;
+        let bytes = Bytes::from_static(b"hello");
+        let etag = EntityTag::from_content(&bytes);
+
+        let generated = respond(client.get("/"), &[], RawResponse::bytes(bytes.clone()).build());
+        let preset =
+            respond(client.get("/"), &[], RawResponse::bytes(bytes).etag(etag.clone()).build());
+
+        assert_eq!(Some(etag.to_string().as_str()), generated.headers().get_one("ETag"));
+        assert_eq!(Some(etag.to_string().as_str()), preset.headers().get_one("ETag"));
+    }
+
+    #[test]
+    fn upload_file_names() {
+        let upload = |raw_name: Option<&'static str>| TempFile::File {
+            file_name:    raw_name.map(FileName::new),
+            content_type: None,
+            path:         Either::Right(PathBuf::from("upload")),
+            len:          0,
+        };
+
+        let name = |raw_name| upload_file_name(&upload(raw_name));
+
+        assert_eq!(Some("evil.html"), name(Some("../evil.html")).as_deref());
+        assert_eq!(Some("a.PNG"), name(Some("a\\b.PNG")).as_deref());
+        assert_eq!(Some("x.abcdefghijklmnop"), name(Some("x.abcdefghijklmnop")).as_deref());
+        assert_eq!(Some("x"), name(Some("x.abcdefghijklmnopq")).as_deref());
+        assert_eq!(Some("x"), name(Some("x.ht ml")).as_deref());
+        assert_eq!(Some("name"), name(Some("name.")).as_deref());
+        assert_eq!(Some("a.gz"), name(Some("a.tar.gz")).as_deref());
+        assert_eq!(None, name(None));
+    }
+
+    #[rocket::async_test]
+    async fn temp_file_persisted_and_deleted_is_gone() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("upload.txt");
+
+        let mut temp_file = TempFile::Buffered {
+            content: b"hello"
+        };
+        temp_file.persist_to(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let error = RawResponse::temp_file(temp_file).open().await.unwrap_err();
+
+        assert!(matches!(error, RawResponseError::Gone));
+        assert_eq!(Err(Status::Gone), error.respond_to(client.get("/").inner()).map(|_| ()));
+    }
+
+    #[rocket::async_test]
+    async fn temp_file_deleted_is_not_found() {
+        let client = client().await;
+        let temp_path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        std::fs::remove_file(&temp_path).unwrap();
+
+        let temp_file = TempFile::File {
+            file_name:    None,
+            content_type: None,
+            path:         Either::Left(temp_path),
+            len:          5,
+        };
+
+        let error = RawResponse::temp_file(temp_file).open().await.unwrap_err();
+
+        assert!(matches!(error, RawResponseError::NotFound));
+        assert_eq!(Err(Status::NotFound), error.respond_to(client.get("/").inner()).map(|_| ()));
+    }
+
+    #[rocket::async_test]
+    async fn temp_file_opened() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("upload.txt");
+
+        let mut temp_file = TempFile::Buffered {
+            content: b"hello"
+        };
+        temp_file.persist_to(&path).await.unwrap();
+
+        let raw_response = RawResponse::temp_file(temp_file).open().await.unwrap().build();
+        std::fs::remove_file(&path).unwrap();
+
+        let mut response = raw_response.respond_to(client.get("/").inner()).unwrap();
+
+        assert_eq!(Status::Ok, response.status());
+        assert!(response.headers().get_one("ETag").is_some());
+        assert_eq!(b"hello", response.body_mut().to_bytes().await.unwrap().as_slice());
+    }
+
+    #[rocket::async_test]
+    async fn temp_file_opened_when_responded() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("upload.txt");
+
+        let mut temp_file = TempFile::Buffered {
+            content: b"hello"
+        };
+        temp_file.persist_to(&path).await.unwrap();
+
+        let mut response = respond(client.get("/"), &[], RawResponse::temp_file(temp_file).build());
+
+        assert_eq!(Status::Ok, response.status());
+        assert!(response.headers().get_one("ETag").is_some());
+        assert!(response.headers().get_one("Last-Modified").is_some());
+        assert_eq!(b"hello", response.body_mut().to_bytes().await.unwrap().as_slice());
+
+        let mut temp_file = TempFile::Buffered {
+            content: b"hello"
+        };
+        temp_file.persist_to(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let response =
+            RawResponse::temp_file(temp_file).build().respond_to(client.get("/").inner());
+
+        assert_eq!(Some(Status::Gone), response.err());
+
+        let temp_path = tempfile::NamedTempFile::new().unwrap().into_temp_path();
+        std::fs::remove_file(&temp_path).unwrap();
+
+        let temp_file = TempFile::File {
+            file_name:    None,
+            content_type: None,
+            path:         Either::Left(temp_path),
+            len:          5,
+        };
+
+        let response = RawResponse::from_temp_file(temp_file, None::<String>, None)
+            .respond_to(client.get("/").inner());
+
+        assert_eq!(Some(Status::NotFound), response.err());
+    }
+}
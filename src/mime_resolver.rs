@@ -0,0 +1,86 @@
+use std::{path::Path, sync::Arc};
+
+use mime::Mime;
+
+/// Resolve the content type of a file from its name. It is used when no content type is set.
+///
+/// ```rust
+/// use rocket_raw_response::{mime::Mime, DefaultMimeResolver, MimeResolver};
+///
+/// struct InHouseMimeResolver;
+///
+/// impl MimeResolver for InHouseMimeResolver {
+///     fn resolve(&self, file_name: &str) -> Option<Mime> {
+///         if file_name.ends_with(".glb") {
+///             return "model/gltf-binary".parse().ok();
+///         }
+///
+///         DefaultMimeResolver.resolve(file_name)
+///     }
+/// }
+///
+/// assert_eq!(
+///     Some("model/gltf-binary"),
+///     InHouseMimeResolver
+///         .resolve("scene.glb")
+///         .as_ref()
+///         .map(Mime::essence_str)
+/// );
+/// ```
+pub trait MimeResolver: Send + Sync {
+    /// Resolve the content type of a file name. Returning `None` means the content type is unknown, so it can still be sniffed if sniffing is enabled.
+    fn resolve(&self, file_name: &str) -> Option<Mime>;
+}
+
+impl<T: MimeResolver + ?Sized> MimeResolver for Arc<T> {
+    #[inline]
+    fn resolve(&self, file_name: &str) -> Option<Mime> {
+        self.as_ref().resolve(file_name)
+    }
+}
+
+/// The default `MimeResolver`, which guesses from the extension with `mime_guess`. An unknown extension is `application/octet-stream` and no extension is `None`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultMimeResolver;
+
+impl MimeResolver for DefaultMimeResolver {
+    #[inline]
+    fn resolve(&self, file_name: &str) -> Option<Mime> {
+        Path::new(file_name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| mime_guess::from_ext(extension).first_or_octet_stream())
+    }
+}
+
+/// A `MimeResolver` registered as Rocket managed state, which is used by every response without its own resolver.
+///
+/// A `RawResponsePro::file` is opened before the request is known, so whether its leading bytes are read to detect the charset is decided by the `DefaultMimeResolver` instead, and a file which only this resolver considers textual gets no `charset` parameter. Set the resolver with `RawResponseBuilder::mime_resolver`, or use `RawResponsePro::lazy_file`, to avoid that.
+///
+/// ```rust
+/// use rocket_raw_response::{DefaultMimeResolver, MimeResolverState};
+///
+/// let rocket =
+///     rocket::build().manage(MimeResolverState::new(DefaultMimeResolver));
+/// ```
+#[derive(Educe)]
+#[educe(Debug)]
+pub struct MimeResolverState {
+    #[educe(Debug(ignore))]
+    resolver: Box<dyn MimeResolver>,
+}
+
+impl MimeResolverState {
+    /// Wrap a `MimeResolver` so that it can be managed by Rocket.
+    #[inline]
+    pub fn new<R: MimeResolver + 'static>(resolver: R) -> MimeResolverState {
+        MimeResolverState {
+            resolver: Box::new(resolver)
+        }
+    }
+
+    #[inline]
+    pub(crate) fn resolver(&self) -> &dyn MimeResolver {
+        self.resolver.as_ref()
+    }
+}
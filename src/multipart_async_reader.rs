@@ -0,0 +1,207 @@
+use std::{
+    collections::{hash_map::RandomState, VecDeque},
+    hash::{BuildHasher, Hash, Hasher},
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::SystemTime,
+};
+
+use rocket::tokio::io::{AsyncRead, AsyncSeek, ReadBuf};
+
+use crate::{range::ByteRange, range_async_reader::RangeAsyncReader};
+
+enum Segment {
+    Bytes(Vec<u8>),
+    Range(ByteRange),
+}
+
+/// Stream a `multipart/byteranges` body whose parts are read from a seekable reader.
+pub(crate) struct MultipartAsyncReader<R> {
+    reader:   Option<R>,
+    part:     Option<RangeAsyncReader<R>>,
+    segments: VecDeque<Segment>,
+    bytes:    Vec<u8>,
+    pos:      usize,
+    len:      u64,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> MultipartAsyncReader<R> {
+    pub(crate) fn new(
+        reader: R,
+        boundary: &str,
+        ranges: &[ByteRange],
+        complete_length: u64,
+        content_type: Option<&str>,
+    ) -> Self {
+        let mut segments = VecDeque::with_capacity(ranges.len() * 2 + 1);
+        let mut len = 0;
+
+        for (i, range) in ranges.iter().enumerate() {
+            let mut head = String::new();
+
+            if i > 0 {
+                head.push_str("\r\n");
+            }
+
+            head.push_str("--");
+            head.push_str(boundary);
+            head.push_str("\r\n");
+
+            if let Some(content_type) = content_type {
+                head.push_str("Content-Type: ");
+                head.push_str(content_type);
+                head.push_str("\r\n");
+            }
+
+            head.push_str("Content-Range: ");
+            head.push_str(&range.content_range(complete_length));
+            head.push_str("\r\n\r\n");
+
+            len += head.len() as u64 + range.len();
+
+            segments.push_back(Segment::Bytes(head.into_bytes()));
+            segments.push_back(Segment::Range(*range));
+        }
+
+        let tail = format!("\r\n--{}--\r\n", boundary);
+
+        len += tail.len() as u64;
+
+        segments.push_back(Segment::Bytes(tail.into_bytes()));
+
+        MultipartAsyncReader {
+            reader: Some(reader),
+            part: None,
+            segments,
+            bytes: Vec::new(),
+            pos: 0,
+            len,
+        }
+    }
+
+    /// The exact length of the whole body.
+    #[inline]
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncRead for MultipartAsyncReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), io::Error>> {
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        loop {
+            if self.pos < self.bytes.len() {
+                let data = &self.bytes[self.pos..];
+
+                let read_size = data.len().min(buf.remaining());
+
+                buf.put_slice(&data[..read_size]);
+
+                self.pos += read_size;
+
+                return Poll::Ready(Ok(()));
+            }
+
+            if let Some(part) = self.part.as_mut() {
+                let filled = buf.filled().len();
+
+                match Pin::new(part).poll_read(ctx, buf) {
+                    Poll::Ready(Ok(())) => {
+                        if buf.filled().len() > filled {
+                            return Poll::Ready(Ok(()));
+                        }
+                    },
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Pending => return Poll::Pending,
+                }
+
+                let part = self.part.take().unwrap();
+
+                self.reader = Some(part.into_inner());
+
+                continue;
+            }
+
+            match self.segments.pop_front() {
+                Some(Segment::Bytes(bytes)) => {
+                    self.bytes = bytes;
+                    self.pos = 0;
+                },
+                Some(Segment::Range(range)) => {
+                    let reader = self.reader.take().unwrap();
+
+                    self.part = Some(RangeAsyncReader::new(reader, range.start, range.len()));
+                },
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+/// Generate a boundary which is unlikely to appear in the content.
+pub(crate) fn generate_boundary() -> String {
+    let state = RandomState::new();
+
+    let mut hasher = state.build_hasher();
+
+    SystemTime::now().hash(&mut hasher);
+
+    let a = hasher.finish();
+
+    a.hash(&mut hasher);
+
+    let b = hasher.finish();
+
+    format!("{:016x}{:016x}", a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use rocket::tokio::io::AsyncReadExt;
+
+    use super::*;
+
+    #[rocket::async_test]
+    async fn len_equals_streamed_bytes() {
+        let data: &[u8] = b"0123456789abcdefghij";
+
+        let ranges = [
+            ByteRange {
+                start: 0, end: 3
+            },
+            ByteRange {
+                start: 10, end: 19
+            },
+        ];
+
+        for content_type in [None, Some("text/plain")] {
+            let mut reader =
+                MultipartAsyncReader::new(Cursor::new(data), "BOUNDARY", &ranges, 20, content_type);
+
+            let len = reader.len();
+
+            let mut body = Vec::new();
+
+            reader.read_to_end(&mut body).await.unwrap();
+
+            assert_eq!(len, body.len() as u64);
+
+            let body = String::from_utf8(body).unwrap();
+
+            assert!(body.starts_with("--BOUNDARY\r\n"));
+            assert!(body.contains("Content-Range: bytes 0-3/20\r\n\r\n0123\r\n--BOUNDARY\r\n"));
+            assert!(body
+                .ends_with("Content-Range: bytes 10-19/20\r\n\r\nabcdefghij\r\n--BOUNDARY--\r\n"));
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    ffi::OsString,
+    fs::{File, Metadata},
+    path::Path,
+};
+
+use rocket::{request::Request, tokio::fs::File as AsyncFile};
+
+use crate::accept_encoding;
+
+/// The content codings of sidecar files and their extensions, in the order of preference.
+const SIDECARS: [(&str, &str); 3] = [("br", "br"), ("zstd", "zst"), ("gzip", "gz")];
+
+/// A precompressed variant of a file, e.g. `app.js.br` next to `app.js`.
+#[derive(Debug)]
+pub(crate) struct Sidecar {
+    pub(crate) coding:   &'static str,
+    pub(crate) file:     AsyncFile,
+    pub(crate) metadata: Metadata,
+}
+
+#[inline]
+fn sidecar_path(path: &Path, extension: &str) -> OsString {
+    let mut sidecar_path = OsString::from(path.as_os_str());
+    sidecar_path.push(".");
+    sidecar_path.push(extension);
+
+    sidecar_path
+}
+
+/// Whether a sidecar file is at least as new as the original file. A sidecar which is older, e.g. because the original has been replaced, is stale.
+#[inline]
+fn is_fresh(metadata: &Metadata, original: &Metadata) -> bool {
+    match (metadata.modified(), original.modified()) {
+        (Ok(modified), Ok(original_modified)) => modified >= original_modified,
+        (Err(_), Ok(_)) => false,
+        (_, Err(_)) => true,
+    }
+}
+
+/// Open the sidecar files of a path which exist and are not stale, with blocking I/O.
+pub(crate) fn open_sidecars(path: &Path, original: &Metadata) -> Vec<Sidecar> {
+    let mut sidecars = Vec::with_capacity(SIDECARS.len());
+
+    for (coding, extension) in SIDECARS {
+        let file = match File::open(sidecar_path(path, extension)) {
+            Ok(file) => file,
+            Err(_) => continue,
+        };
+
+        match file.metadata() {
+            Ok(metadata) if metadata.is_file() && is_fresh(&metadata, original) => {
+                sidecars.push(Sidecar {
+                    coding,
+                    file: AsyncFile::from_std(file),
+                    metadata,
+                })
+            },
+            _ => continue,
+        }
+    }
+
+    sidecars
+}
+
+/// Take the sidecar file preferred by the `Accept-Encoding` header of a request, or `None` if the original file should be used.
+pub(crate) fn select(sidecars: Vec<Sidecar>, request: &Request<'_>) -> Option<Sidecar> {
+    let codings: Vec<&str> = sidecars.iter().map(|sidecar| sidecar.coding).collect();
+
+    let coding = accept_encoding::preferred(request, &codings)?;
+
+    sidecars.into_iter().find(|sidecar| sidecar.coding == coding)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, thread, time::Duration};
+
+    use rocket::http::Status;
+
+    use super::*;
+    use crate::{
+        testing::{client, respond},
+        RawResponse,
+    };
+
+    /// Create `x.js`, a fresh `x.js.br` and a stale `x.js.gz` in `dir`.
+    fn create_files(dir: &Path) {
+        fs::write(dir.join("x.js.gz"), "gzip").unwrap();
+
+        let gz_modified = fs::metadata(dir.join("x.js.gz")).unwrap().modified().unwrap();
+
+        // wait until the clock of the file system has moved on, so that the original is newer
+        loop {
+            fs::write(dir.join("x.js"), "alert(1);").unwrap();
+
+            if fs::metadata(dir.join("x.js")).unwrap().modified().unwrap() > gz_modified {
+                break;
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        fs::write(dir.join("x.js.br"), "brotli").unwrap();
+    }
+
+    #[rocket::async_test]
+    async fn respond_sidecars() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("x.js");
+
+        create_files(dir.path());
+
+        let build = || RawResponse::file(path.as_path()).precompressed(true).build();
+
+        let mut original =
+            respond(client.get("/"), &[("Accept-Encoding", "identity")], build().await.unwrap());
+
+        let content_type = original.headers().get_one("Content-Type").unwrap().to_string();
+        let etag = original.headers().get_one("ETag").unwrap().to_string();
+
+        assert!(content_type.contains("javascript"));
+        assert_eq!(None, original.headers().get_one("Content-Encoding"));
+        assert_eq!(b"alert(1);", original.body_mut().to_bytes().await.unwrap().as_slice());
+
+        let mut response =
+            respond(client.get("/"), &[("Accept-Encoding", "gzip, br")], build().await.unwrap());
+
+        assert_eq!(Status::Ok, response.status());
+        assert_eq!(Some("br"), response.headers().get_one("Content-Encoding"));
+        assert_eq!(Some("Accept-Encoding"), response.headers().get_one("Vary"));
+        assert_eq!(Some(content_type.as_str()), response.headers().get_one("Content-Type"));
+        assert!(response.headers().get_one("ETag").unwrap().ends_with(r#"-br""#));
+        assert_ne!(Some(etag.as_str()), response.headers().get_one("ETag"));
+        assert_eq!(b"brotli", response.body_mut().to_bytes().await.unwrap().as_slice());
+    }
+
+    #[rocket::async_test]
+    async fn respond_without_stale_sidecars() {
+        let client = client().await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("x.js");
+
+        create_files(dir.path());
+
+        let responses = [
+            RawResponse::file(path.as_path()).precompressed(true).build().await.unwrap(),
+            RawResponse::lazy_file(path.as_path()).precompressed(true).build(),
+        ];
+
+        for raw_response in responses {
+            let mut response =
+                respond(client.get("/"), &[("Accept-Encoding", "gzip")], raw_response);
+
+            assert_eq!(Status::Ok, response.status());
+            assert_eq!(None, response.headers().get_one("Content-Encoding"));
+            assert_eq!(Some("Accept-Encoding"), response.headers().get_one("Vary"));
+            assert_eq!(b"alert(1);", response.body_mut().to_bytes().await.unwrap().as_slice());
+        }
+    }
+}
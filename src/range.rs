@@ -0,0 +1,229 @@
+use rocket::{http::Method, request::Request};
+
+use crate::conditional::Validators;
+
+/// A satisfiable byte range. Both `start` and `end` are inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ByteRange {
+    pub(crate) start: u64,
+    pub(crate) end:   u64,
+}
+
+impl ByteRange {
+    #[inline]
+    pub(crate) fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// The value of the `Content-Range` header for this range of a `complete_length` bytes long representation.
+    #[inline]
+    pub(crate) fn content_range(&self, complete_length: u64) -> String {
+        format!("bytes {}-{}/{}", self.start, self.end, complete_length)
+    }
+}
+
+/// The way a `Range` header of a request should be answered.
+#[derive(Debug)]
+pub(crate) enum Ranges {
+    /// The whole representation should be sent with `200 OK`.
+    Full,
+    /// Only the given range should be sent with `206 Partial Content`.
+    Partial(ByteRange),
+    /// The given ranges should be sent with `206 Partial Content` as a `multipart/byteranges` body.
+    Multiple(Vec<ByteRange>),
+    /// The request should be answered with `416 Range Not Satisfiable`.
+    Unsatisfiable,
+}
+
+impl Ranges {
+    /// Resolve the `Range` header of a request against a representation which is `len` bytes long.
+    ///
+    /// Overlapping and adjacent ranges are coalesced. A missing or malformed header, a unit other than `bytes` and a request with more than `max_ranges` ranges are all answered with the full representation.
+    ///
+    /// The ranges are also ignored if the `If-Range` header does not match the validators, so that a resumed download never mixes two versions of the content.
+    pub(crate) fn from_request(
+        request: &Request<'_>,
+        len: u64,
+        max_ranges: usize,
+        validators: &Validators,
+    ) -> Ranges {
+        if max_ranges == 0 || !matches!(request.method(), Method::Get | Method::Head) {
+            return Ranges::Full;
+        }
+
+        let value = match request.headers().get_one("Range") {
+            Some(value) => value,
+            None => return Ranges::Full,
+        };
+
+        if !validators.if_range(request) {
+            return Ranges::Full;
+        }
+
+        match parse_range_header(value, len) {
+            Some(ranges) => {
+                let ranges = coalesce(ranges);
+
+                match ranges.len() {
+                    0 => Ranges::Unsatisfiable,
+                    n if n > max_ranges => Ranges::Full,
+                    1 => Ranges::Partial(ranges[0]),
+                    _ => Ranges::Multiple(ranges),
+                }
+            },
+            None => Ranges::Full,
+        }
+    }
+}
+
+/// Sort ranges and merge the ones which overlap or are adjacent to each other.
+fn coalesce(mut ranges: Vec<ByteRange>) -> Vec<ByteRange> {
+    ranges.sort_unstable_by_key(|range| range.start);
+
+    let mut coalesced: Vec<ByteRange> = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        match coalesced.last_mut() {
+            Some(last) if range.start <= last.end.saturating_add(1) => {
+                last.end = last.end.max(range.end);
+            },
+            _ => coalesced.push(range),
+        }
+    }
+
+    coalesced
+}
+
+/// Parse the value of a `Range` header. Returns `None` if the value is malformed, or the satisfiable ranges in it.
+fn parse_range_header(value: &str, len: u64) -> Option<Vec<ByteRange>> {
+    let (unit, set) = value.split_once('=')?;
+
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+
+    let mut ranges = Vec::new();
+    let mut has_spec = false;
+
+    for spec in set.split(',') {
+        let spec = spec.trim();
+
+        if spec.is_empty() {
+            continue;
+        }
+
+        has_spec = true;
+
+        let (first, last) = spec.split_once('-')?;
+        let (first, last) = (first.trim(), last.trim());
+
+        if first.is_empty() {
+            // suffix-range: the last `suffix` bytes
+            let suffix = parse_digits(last)?;
+
+            if suffix > 0 && len > 0 {
+                ranges.push(ByteRange {
+                    start: len.saturating_sub(suffix), end: len - 1
+                });
+            }
+        } else {
+            let first = parse_digits(first)?;
+
+            let last = if last.is_empty() {
+                None
+            } else {
+                let last = parse_digits(last)?;
+
+                if last < first {
+                    return None;
+                }
+
+                Some(last)
+            };
+
+            if first < len {
+                ranges.push(ByteRange {
+                    start: first,
+                    end:   last.map(|last| last.min(len - 1)).unwrap_or(len - 1),
+                });
+            }
+        }
+    }
+
+    if has_spec {
+        Some(ranges)
+    } else {
+        None
+    }
+}
+
+#[inline]
+fn parse_digits(s: &str) -> Option<u64> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[inline]
+    fn range(start: u64, end: u64) -> ByteRange {
+        ByteRange {
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn parse_bounded_and_open_ranges() {
+        assert_eq!(Some(vec![range(0, 4)]), parse_range_header("bytes=0-4", 10));
+        assert_eq!(Some(vec![range(5, 9)]), parse_range_header("bytes=5-", 10));
+        assert_eq!(Some(vec![range(5, 9)]), parse_range_header("bytes=5-100", 10));
+        assert_eq!(Some(vec![range(0, 0), range(2, 3)]), parse_range_header("bytes=0-0, 2-3", 10));
+        assert_eq!(Some(vec![range(1, 2)]), parse_range_header("Bytes = 1-2", 10));
+    }
+
+    #[test]
+    fn parse_suffix_ranges() {
+        assert_eq!(Some(vec![range(7, 9)]), parse_range_header("bytes=-3", 10));
+        assert_eq!(Some(vec![range(0, 9)]), parse_range_header("bytes=-100", 10));
+        assert_eq!(Some(vec![]), parse_range_header("bytes=-0", 10));
+        assert_eq!(Some(vec![]), parse_range_header("bytes=-3", 0));
+    }
+
+    #[test]
+    fn parse_unsatisfiable_ranges() {
+        assert_eq!(Some(vec![]), parse_range_header("bytes=10-", 10));
+        assert_eq!(Some(vec![]), parse_range_header("bytes=10-20", 10));
+        assert_eq!(Some(vec![range(0, 1)]), parse_range_header("bytes=10-20, 0-1", 10));
+    }
+
+    #[test]
+    fn coalesce_overlapping_and_adjacent_ranges() {
+        assert_eq!(vec![range(0, 9)], coalesce(vec![range(5, 9), range(0, 6)]));
+        assert_eq!(vec![range(0, 9)], coalesce(vec![range(0, 4), range(5, 9)]));
+        assert_eq!(vec![range(0, 9)], coalesce(vec![range(2, 3), range(0, 9)]));
+        assert_eq!(vec![range(0, 3), range(5, 9)], coalesce(vec![range(5, 9), range(0, 3)]));
+        assert_eq!(
+            vec![range(0, 2), range(4, 6)],
+            coalesce(vec![range(4, 6), range(0, 2), range(1, 1)])
+        );
+    }
+
+    #[test]
+    fn parse_malformed_ranges() {
+        assert_eq!(None, parse_range_header("bytes=4-3", 10));
+        assert_eq!(None, parse_range_header("items=0-4", 10));
+        assert_eq!(None, parse_range_header("0-4", 10));
+        assert_eq!(None, parse_range_header("bytes=", 10));
+        assert_eq!(None, parse_range_header("bytes=,", 10));
+        assert_eq!(None, parse_range_header("bytes=-", 10));
+        assert_eq!(None, parse_range_header("bytes=a-4", 10));
+        assert_eq!(None, parse_range_header("bytes=+1-4", 10));
+        assert_eq!(None, parse_range_header("bytes=0-4, x", 10));
+    }
+}
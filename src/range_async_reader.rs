@@ -0,0 +1,69 @@
+use std::{
+    io::{self, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use rocket::tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, ReadBuf, Take};
+
+/// Read `len` bytes starting at `start` from a seekable reader.
+pub(crate) struct RangeAsyncReader<R> {
+    inner:   Take<R>,
+    start:   Option<u64>,
+    seeking: bool,
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> RangeAsyncReader<R> {
+    #[inline]
+    pub(crate) fn new(reader: R, start: u64, len: u64) -> Self {
+        RangeAsyncReader {
+            inner: reader.take(len), start: Some(start), seeking: false
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncRead for RangeAsyncReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), io::Error>> {
+        if let Some(start) = self.start {
+            if !self.seeking {
+                Pin::new(self.inner.get_mut()).start_seek(SeekFrom::Start(start))?;
+
+                self.seeking = true;
+            }
+
+            match Pin::new(self.inner.get_mut()).poll_complete(ctx) {
+                Poll::Ready(Ok(_)) => {
+                    self.start = None;
+                    self.seeking = false;
+                },
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        let filled = buf.filled().len();
+
+        match Pin::new(&mut self.inner).poll_read(ctx, buf) {
+            Poll::Ready(Ok(())) => {
+                if buf.filled().len() == filled && buf.remaining() > 0 && self.inner.limit() > 0 {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "the reader ended before the end of the range",
+                    )));
+                }
+
+                Poll::Ready(Ok(()))
+            },
+            poll => poll,
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use mime::Mime;
+
+/// How a response with an active content type, which a browser would execute, is defused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActiveContentAction {
+    /// Force the `attachment` disposition, so that it is downloaded instead of rendered.
+    #[default]
+    Attachment,
+    /// Replace the content type with `application/octet-stream`.
+    OctetStream,
+}
+
+/// A policy for serving untrusted content, e.g. user uploads, from the origin of the application.
+///
+/// Every response gets `X-Content-Type-Options: nosniff`. A response whose content type is active, i.e. HTML, SVG, XML or JavaScript, also gets `Content-Security-Policy: sandbox` and is defused by the `ActiveContentAction`, unless its content type is allowed.
+///
+/// A response without a valid content type is treated as active too, because a browser sniffs its content and may render it as HTML.
+///
+/// ```rust
+/// use rocket_raw_response::{mime, ActiveContentAction, SafetyPolicy};
+///
+/// let safety_policy = SafetyPolicy::new()
+///     .action(ActiveContentAction::OctetStream)
+///     .allow(mime::IMAGE_SVG);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SafetyPolicy {
+    action:  ActiveContentAction,
+    allowed: Vec<Mime>,
+}
+
+impl SafetyPolicy {
+    /// Create a policy which forces the `attachment` disposition for all active content types.
+    #[inline]
+    pub fn new() -> SafetyPolicy {
+        SafetyPolicy::default()
+    }
+
+    /// Set how active content is defused. The default value is `ActiveContentAction::Attachment`.
+    #[inline]
+    pub fn action(mut self, action: ActiveContentAction) -> SafetyPolicy {
+        self.action = action;
+
+        self
+    }
+
+    /// Allow an active content type to be served as it is, still sandboxed. Parameters are ignored.
+    #[inline]
+    pub fn allow(mut self, content_type: Mime) -> SafetyPolicy {
+        self.allowed.push(content_type);
+
+        self
+    }
+
+    /// Decide what to do with a content type. Returns whether it is active and, if so, the action to defuse it unless it is allowed.
+    pub(crate) fn check(&self, content_type: Option<&str>) -> (bool, Option<ActiveContentAction>) {
+        let mime = match content_type.and_then(|content_type| content_type.parse::<Mime>().ok()) {
+            Some(mime) => mime,
+            None => return (true, Some(self.action)),
+        };
+
+        if !is_active(&mime) {
+            return (false, None);
+        }
+
+        if self.allowed.iter().any(|allowed| allowed.essence_str() == mime.essence_str()) {
+            (true, None)
+        } else {
+            (true, Some(self.action))
+        }
+    }
+}
+
+/// Whether a browser may execute content of the type in the origin it is served from.
+fn is_active(mime: &Mime) -> bool {
+    if mime.suffix() == Some(mime::XML) {
+        return true;
+    }
+
+    matches!(
+        mime.essence_str(),
+        "text/html"
+            | "text/xml"
+            | "text/xsl"
+            | "text/javascript"
+            | "text/ecmascript"
+            | "application/xml"
+            | "application/javascript"
+            | "application/ecmascript"
+            | "application/x-javascript"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{client, respond},
+        RawResponse,
+    };
+
+    #[test]
+    fn check_active_content_types() {
+        let policy = SafetyPolicy::new();
+
+        assert_eq!((false, None), policy.check(Some("image/png")));
+        assert_eq!((false, None), policy.check(Some("text/plain; charset=utf-8")));
+        assert_eq!((true, Some(ActiveContentAction::Attachment)), policy.check(Some("text/html")));
+        assert_eq!(
+            (true, Some(ActiveContentAction::Attachment)),
+            policy.check(Some("image/svg+xml"))
+        );
+        assert_eq!(
+            (true, Some(ActiveContentAction::Attachment)),
+            policy.check(Some("application/javascript; charset=utf-8"))
+        );
+    }
+
+    #[test]
+    fn check_allowed_content_types() {
+        let policy =
+            SafetyPolicy::new().action(ActiveContentAction::OctetStream).allow(mime::IMAGE_SVG);
+
+        assert_eq!((true, None), policy.check(Some("image/svg+xml")));
+        assert_eq!((true, Some(ActiveContentAction::OctetStream)), policy.check(Some("text/html")));
+    }
+
+    #[test]
+    fn check_missing_content_types() {
+        let policy = SafetyPolicy::new().action(ActiveContentAction::OctetStream);
+
+        assert_eq!((true, Some(ActiveContentAction::OctetStream)), policy.check(None));
+        assert_eq!(
+            (true, Some(ActiveContentAction::OctetStream)),
+            policy.check(Some("not a type"))
+        );
+    }
+
+    #[rocket::async_test]
+    async fn respond_attachment() {
+        let client = client().await;
+
+        let response = respond(
+            client.get("/"),
+            &[],
+            RawResponse::slice(b"<script>alert(1);</script>")
+                .file_name("x.html")
+                .content_type(mime::TEXT_HTML)
+                .safety_policy(SafetyPolicy::new())
+                .build(),
+        );
+
+        assert_eq!(Some("nosniff"), response.headers().get_one("X-Content-Type-Options"));
+        assert_eq!(Some("sandbox"), response.headers().get_one("Content-Security-Policy"));
+        assert_eq!(Some("text/html"), response.headers().get_one("Content-Type"));
+        assert!(response
+            .headers()
+            .get_one("Content-Disposition")
+            .unwrap()
+            .starts_with("attachment;"));
+
+        let response = respond(
+            client.get("/"),
+            &[],
+            RawResponse::slice(b"hello")
+                .file_name("x.txt")
+                .content_type(mime::TEXT_PLAIN)
+                .safety_policy(SafetyPolicy::new())
+                .build(),
+        );
+
+        assert_eq!(Some("nosniff"), response.headers().get_one("X-Content-Type-Options"));
+        assert_eq!(None, response.headers().get_one("Content-Security-Policy"));
+        assert!(response.headers().get_one("Content-Disposition").unwrap().starts_with("inline;"));
+    }
+
+    #[rocket::async_test]
+    async fn respond_octet_stream() {
+        let client = client().await;
+
+        let response = respond(
+            client.get("/"),
+            &[],
+            RawResponse::slice(b"<script>alert(1);</script>")
+                .file_name("x.html")
+                .content_type(mime::TEXT_HTML)
+                .safety_policy(SafetyPolicy::new().action(ActiveContentAction::OctetStream))
+                .build(),
+        );
+
+        assert_eq!(Some("nosniff"), response.headers().get_one("X-Content-Type-Options"));
+        assert_eq!(Some("sandbox"), response.headers().get_one("Content-Security-Policy"));
+        assert_eq!(Some("application/octet-stream"), response.headers().get_one("Content-Type"));
+        assert!(response.headers().get_one("Content-Disposition").unwrap().starts_with("inline;"));
+    }
+}
@@ -0,0 +1,179 @@
+use mime::Mime;
+
+/// The number of leading bytes which are inspected.
+pub(crate) const SNIFF_LEN: usize = 512;
+
+/// Signatures at the start of the content and their content types.
+const SIGNATURES: [(&[u8], &str); 21] = [
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"BM", "image/bmp"),
+    (b"\x00\x00\x01\x00", "image/x-icon"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"PK\x05\x06", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (b"BZh", "application/x-bzip2"),
+    (b"\xfd7zXZ\x00", "application/x-xz"),
+    (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (b"Rar!\x1a\x07", "application/vnd.rar"),
+    (b"\x1a\x45\xdf\xa3", "video/webm"),
+    (b"OggS", "audio/ogg"),
+    (b"fLaC", "audio/flac"),
+    (b"ID3", "audio/mpeg"),
+    (b"\x00asm", "application/wasm"),
+    (b"wOF2", "font/woff2"),
+];
+
+/// Guess the content type from the leading bytes of the content. Returns `None` for empty content.
+///
+/// Text is never guessed as HTML or any other active type, so valid UTF-8 without control characters is `text/plain; charset=utf-8` and anything else unknown is `application/octet-stream`.
+pub(crate) fn sniff(bytes: &[u8]) -> Option<Mime> {
+    if bytes.is_empty() {
+        return None;
+    }
+
+    let bytes = &bytes[..bytes.len().min(SNIFF_LEN)];
+
+    let content_type = if let Some((_, content_type)) =
+        SIGNATURES.iter().find(|(signature, _)| bytes.starts_with(signature))
+    {
+        content_type
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" {
+        match &bytes[8..12] {
+            b"WEBP" => "image/webp",
+            b"WAVE" => "audio/wav",
+            b"AVI " => "video/x-msvideo",
+            _ => "application/octet-stream",
+        }
+    } else if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        match &bytes[8..12] {
+            b"avif" | b"avis" => "image/avif",
+            b"heic" | b"heix" | b"mif1" => "image/heic",
+            b"qt  " => "video/quicktime",
+            b"M4A " => "audio/mp4",
+            _ => "video/mp4",
+        }
+    } else if is_text(bytes) {
+        "text/plain; charset=utf-8"
+    } else {
+        "application/octet-stream"
+    };
+
+    content_type.parse().ok()
+}
+
+/// Whether the bytes are UTF-8 text without control characters. A character cut off at the end is allowed.
+fn is_text(bytes: &[u8]) -> bool {
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+
+    if bytes
+        .iter()
+        .any(|&b| (b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C | 0x1B)) || b == 0x7F)
+    {
+        return false;
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        Err(error) => error.error_len().is_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sniff_essence(bytes: &[u8]) -> Option<String> {
+        sniff(bytes).map(|content_type| content_type.essence_str().to_string())
+    }
+
+    #[test]
+    fn sniff_signatures() {
+        let cases: [(&[u8], &str); 21] = [
+            (b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR", "image/png"),
+            (b"\xff\xd8\xff\xe0\x00\x10JFIF", "image/jpeg"),
+            (b"GIF87a\x01\x00", "image/gif"),
+            (b"GIF89a\x01\x00", "image/gif"),
+            (b"BM\x36\x00\x00\x00", "image/bmp"),
+            (b"\x00\x00\x01\x00\x01\x00", "image/x-icon"),
+            (b"%PDF-1.7\n", "application/pdf"),
+            (b"PK\x03\x04\x14\x00", "application/zip"),
+            (b"PK\x05\x06\x00\x00", "application/zip"),
+            (b"\x1f\x8b\x08\x00", "application/gzip"),
+            (b"\x28\xb5\x2f\xfd\x04\x00", "application/zstd"),
+            (b"BZh91AY", "application/x-bzip2"),
+            (b"\xfd7zXZ\x00\x00", "application/x-xz"),
+            (b"7z\xbc\xaf\x27\x1c\x00\x04", "application/x-7z-compressed"),
+            (b"Rar!\x1a\x07\x01\x00", "application/vnd.rar"),
+            (b"\x1a\x45\xdf\xa3\x9f\x42", "video/webm"),
+            (b"OggS\x00\x02", "audio/ogg"),
+            (b"fLaC\x00\x00", "audio/flac"),
+            (b"ID3\x04\x00", "audio/mpeg"),
+            (b"\x00asm\x01\x00\x00\x00", "application/wasm"),
+            (b"wOF2\x00\x01", "font/woff2"),
+        ];
+
+        for (bytes, content_type) in cases {
+            assert_eq!(Some(content_type), sniff_essence(bytes).as_deref(), "{:?}", bytes);
+        }
+    }
+
+    #[test]
+    fn sniff_containers() {
+        let cases: [(&[u8], &str); 11] = [
+            (b"RIFF\x24\x00\x00\x00WEBPVP8 ", "image/webp"),
+            (b"RIFF\x24\x00\x00\x00WAVEfmt ", "audio/wav"),
+            (b"RIFF\x24\x00\x00\x00AVI LIST", "video/x-msvideo"),
+            (b"RIFF\x24\x00\x00\x00ABCD", "application/octet-stream"),
+            (b"\x00\x00\x00\x1cftypavif", "image/avif"),
+            (b"\x00\x00\x00\x1cftypheic", "image/heic"),
+            (b"\x00\x00\x00\x1cftypmif1", "image/heic"),
+            (b"\x00\x00\x00\x14ftypqt  ", "video/quicktime"),
+            (b"\x00\x00\x00\x20ftypM4A ", "audio/mp4"),
+            (b"\x00\x00\x00\x20ftypisom", "video/mp4"),
+            (b"RIFF text", "text/plain"),
+        ];
+
+        for (bytes, content_type) in cases {
+            assert_eq!(Some(content_type), sniff_essence(bytes).as_deref(), "{:?}", bytes);
+        }
+    }
+
+    #[test]
+    fn sniff_text() {
+        assert_eq!(None, sniff(b""));
+        assert_eq!(
+            Some("text/plain; charset=utf-8"),
+            sniff(b"<html>\n").map(|content_type| content_type.to_string()).as_deref()
+        );
+        assert_eq!(Some("text/plain"), sniff_essence("中文\r\n\tok".as_bytes()).as_deref());
+        assert_eq!(Some("text/plain"), sniff_essence(b"\xef\xbb\xbfwith a BOM").as_deref());
+        assert_eq!(Some("text/plain"), sniff_essence(b"\x1b[31mred\x1b[0m").as_deref());
+
+        assert_eq!(Some("application/octet-stream"), sniff_essence(b"a\x00b").as_deref());
+        assert_eq!(Some("application/octet-stream"), sniff_essence(b"a\x07b").as_deref());
+        assert_eq!(Some("application/octet-stream"), sniff_essence(b"a\x7fb").as_deref());
+        assert_eq!(Some("application/octet-stream"), sniff_essence(b"a\xffb").as_deref());
+        assert_eq!(Some("application/octet-stream"), sniff_essence(b"a\xe4\xb8b").as_deref());
+    }
+
+    #[test]
+    fn sniff_text_cut_off_at_sniff_len() {
+        // `中` is 3 bytes long, so the one starting at 510 is cut off after 2 bytes
+        let mut bytes = vec![b'a'; SNIFF_LEN - 2];
+        bytes.extend_from_slice("中中".as_bytes());
+
+        assert_eq!(Some("text/plain"), sniff_essence(&bytes).as_deref());
+
+        // a character which is cut off before the end is still invalid
+        let mut bytes = vec![b'a'; SNIFF_LEN - 4];
+        bytes.extend_from_slice(b"\xe4\xb8a");
+        bytes.extend_from_slice("中".as_bytes());
+
+        assert_eq!(Some("application/octet-stream"), sniff_essence(&bytes).as_deref());
+    }
+}
@@ -0,0 +1,127 @@
+use std::io::{self, Read};
+
+use bytes::Bytes;
+use rocket::{
+    futures::{future, stream, Stream, StreamExt},
+    tokio::{
+        sync::mpsc::{self, Sender},
+        task,
+    },
+};
+
+/// The size of a chunk read from a blocking reader.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of chunks which can be read ahead of the response.
+const CHANNEL_CAPACITY: usize = 4;
+
+/// Read a blocking reader in chunks on the blocking pool, starting at the first poll. Reading stops once the stream is dropped, and a panic of the reader is returned as an error after the chunks it has read.
+pub(crate) fn chunks<R: Read + Send + 'static>(
+    reader: R,
+) -> impl Stream<Item = Result<Bytes, io::Error>> + Send {
+    stream::once(async move {
+        let (sender, mut receiver) = mpsc::channel(CHANNEL_CAPACITY);
+
+        let task = task::spawn_blocking(move || read_chunks(reader, sender));
+
+        // the channel is also closed when the reader panics, so the task tells whether the data really ended
+        let end = stream::once(task).filter_map(|result| {
+            future::ready(
+                result.err().map(|error| Err(io::Error::new(io::ErrorKind::Other, error))),
+            )
+        });
+
+        stream::poll_fn(move |ctx| receiver.poll_recv(ctx)).chain(end)
+    })
+    .flatten()
+}
+
+fn read_chunks<R: Read>(mut reader: R, sender: Sender<Result<Bytes, io::Error>>) {
+    loop {
+        let mut buffer = vec![0; CHUNK_SIZE];
+
+        let chunk = match reader.read(&mut buffer) {
+            Ok(0) => return,
+            Ok(len) => {
+                buffer.truncate(len);
+
+                Ok(Bytes::from(buffer))
+            },
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => Err(error),
+        };
+
+        let failed = chunk.is_err();
+
+        if sender.blocking_send(chunk).is_err() || failed {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reader which produces `hello` twice and then fails the way `fail` says.
+    struct HelloReader {
+        reads: usize,
+        fail:  fn() -> io::Result<usize>,
+    }
+
+    impl Read for HelloReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.reads += 1;
+
+            if self.reads > 2 {
+                return (self.fail)();
+            }
+
+            buf[..5].copy_from_slice(b"hello");
+
+            Ok(5)
+        }
+    }
+
+    async fn collect(fail: fn() -> io::Result<usize>) -> (Vec<u8>, Option<io::Error>) {
+        let mut chunks = Box::pin(chunks(HelloReader {
+            reads: 0,
+            fail,
+        }));
+
+        let mut data = Vec::new();
+
+        while let Some(chunk) = chunks.next().await {
+            match chunk {
+                Ok(chunk) => data.extend_from_slice(&chunk),
+                Err(error) => return (data, Some(error)),
+            }
+        }
+
+        (data, None)
+    }
+
+    #[rocket::async_test]
+    async fn chunks_until_eof() {
+        let (data, error) = collect(|| Ok(0)).await;
+
+        assert_eq!(b"hellohello", data.as_slice());
+        assert!(error.is_none());
+    }
+
+    #[rocket::async_test]
+    async fn chunks_until_error() {
+        let (data, error) = collect(|| Err(io::Error::new(io::ErrorKind::Other, "broken"))).await;
+
+        assert_eq!(b"hellohello", data.as_slice());
+        assert_eq!("broken", error.unwrap().to_string());
+    }
+
+    #[rocket::async_test]
+    async fn chunks_until_panic() {
+        let (data, error) = collect(|| panic!("the reader panics")).await;
+
+        assert_eq!(b"hellohello", data.as_slice());
+        assert!(error.is_some());
+    }
+}
@@ -0,0 +1,98 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use bytes::{Buf, Bytes};
+use rocket::{
+    futures::Stream,
+    tokio::io::{AsyncRead, ReadBuf},
+};
+
+/// Read the chunks of a stream. An error of the stream is returned as a read error, which aborts the connection.
+pub(crate) struct StreamAsyncReader<S> {
+    stream: Pin<Box<S>>,
+    chunk:  Bytes,
+}
+
+impl<S> StreamAsyncReader<S> {
+    #[inline]
+    pub(crate) fn new(stream: S) -> Self {
+        StreamAsyncReader {
+            stream: Box::pin(stream), chunk: Bytes::new()
+        }
+    }
+}
+
+impl<S: Stream<Item = Result<Bytes, io::Error>>> AsyncRead for StreamAsyncReader<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), io::Error>> {
+        while self.chunk.is_empty() {
+            match ready!(self.stream.as_mut().poll_next(ctx)) {
+                Some(Ok(chunk)) => self.chunk = chunk,
+                Some(Err(error)) => return Poll::Ready(Err(error)),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let len = self.chunk.len().min(buf.remaining());
+
+        buf.put_slice(&self.chunk[..len]);
+        self.chunk.advance(len);
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::{futures::stream, response::Responder, tokio::io::AsyncReadExt};
+
+    use super::*;
+    use crate::{testing::client, RawResponse};
+
+    fn chunks(fail: bool) -> impl Stream<Item = Result<Bytes, io::Error>> {
+        let mut chunks =
+            vec![Ok(Bytes::from_static(b"he")), Ok(Bytes::new()), Ok(Bytes::from_static(b"llo"))];
+
+        if fail {
+            chunks.push(Err(io::Error::new(io::ErrorKind::Other, "broken")));
+        }
+
+        stream::iter(chunks)
+    }
+
+    #[rocket::async_test]
+    async fn read_until_end() {
+        let mut data = Vec::new();
+
+        StreamAsyncReader::new(chunks(false)).read_to_end(&mut data).await.unwrap();
+
+        assert_eq!(b"hello", data.as_slice());
+    }
+
+    #[rocket::async_test]
+    async fn read_until_error() {
+        let mut reader = StreamAsyncReader::new(chunks(true));
+        let mut data = [0; 5];
+
+        reader.read_exact(&mut data).await.unwrap();
+
+        assert_eq!(b"hello", &data);
+        assert_eq!("broken", reader.read(&mut data).await.unwrap_err().to_string());
+    }
+
+    #[rocket::async_test]
+    async fn respond_failed_stream() {
+        let client = client().await;
+
+        let mut response =
+            RawResponse::stream(chunks(true)).build().respond_to(client.get("/").inner()).unwrap();
+
+        assert!(response.body_mut().to_bytes().await.is_err());
+    }
+}
@@ -1,6 +1,5 @@
 use std::{
-    fs::File,
-    io::{self},
+    io::{self, SeekFrom},
     pin::Pin,
     task::{Context, Poll},
 };
@@ -9,7 +8,7 @@ use rocket::{
     fs::TempFile,
     tokio::{
         fs::File as AsyncFile,
-        io::{AsyncRead, ReadBuf},
+        io::{AsyncRead, AsyncSeek, ReadBuf},
     },
 };
 
@@ -18,6 +17,7 @@ enum TempFileAsyncReaderInner<'v> {
     Buffered { content: &'v [u8], pos: usize },
 }
 
+/// Read a temporary file, which is kept alive so that an unpersisted file is not deleted while it is read.
 pub(crate) struct TempFileAsyncReader<'v> {
     #[allow(dead_code)]
     temp_file: Box<TempFile<'v>>,
@@ -25,41 +25,38 @@ pub(crate) struct TempFileAsyncReader<'v> {
 }
 
 impl<'v> TempFileAsyncReader<'v> {
-    pub(crate) fn from(temp_file: Box<TempFile<'v>>) -> Result<Self, io::Error> {
-        let content = if let TempFile::Buffered {
-            content,
-        } = temp_file.as_ref()
-        {
-            Some(*content)
-        } else {
-            None
-        };
-
-        if let Some(content) = content {
-            return Ok(TempFileAsyncReader {
-                temp_file,
-                inner: TempFileAsyncReaderInner::Buffered {
+    /// Create a reader of a temporary file. A file stored on disk is read from `async_file`, which has been opened from its path.
+    pub(crate) fn new(temp_file: Box<TempFile<'v>>, async_file: Option<AsyncFile>) -> Self {
+        let inner = match (temp_file.as_ref(), async_file) {
+            (
+                TempFile::Buffered {
                     content,
-                    pos: 0,
                 },
-            });
-        }
-
-        let async_file = if let TempFile::File {
-            path, ..
-        } = temp_file.as_ref()
-        {
-            AsyncFile::from_std(File::open(path)?)
-        } else {
-            unreachable!()
+                _,
+            ) => TempFileAsyncReaderInner::Buffered {
+                content,
+                pos: 0,
+            },
+            (
+                TempFile::File {
+                    ..
+                },
+                Some(async_file),
+            ) => TempFileAsyncReaderInner::File {
+                async_file,
+            },
+            (
+                TempFile::File {
+                    ..
+                },
+                None,
+            ) => unreachable!("a temporary file stored on disk is opened before it is read"),
         };
 
-        Ok(TempFileAsyncReader {
+        TempFileAsyncReader {
             temp_file,
-            inner: TempFileAsyncReaderInner::File {
-                async_file,
-            },
-        })
+            inner,
+        }
     }
 }
 
@@ -69,9 +66,7 @@ impl<'v> AsyncRead for TempFileAsyncReader<'v> {
         ctx: &mut Context<'_>,
         buf: &mut ReadBuf<'_>,
     ) -> Poll<Result<(), io::Error>> {
-        let inner = &mut self.inner;
-
-        match inner {
+        match &mut self.inner {
             TempFileAsyncReaderInner::File {
                 async_file,
             } => Pin::new(async_file).poll_read(ctx, buf),
@@ -92,3 +87,49 @@ impl<'v> AsyncRead for TempFileAsyncReader<'v> {
         }
     }
 }
+
+impl<'v> AsyncSeek for TempFileAsyncReader<'v> {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> Result<(), io::Error> {
+        match &mut self.inner {
+            TempFileAsyncReaderInner::File {
+                async_file,
+            } => Pin::new(async_file).start_seek(position),
+            TempFileAsyncReaderInner::Buffered {
+                content,
+                pos,
+            } => {
+                let new_pos = match position {
+                    SeekFrom::Start(offset) => Some(offset),
+                    SeekFrom::End(offset) => (content.len() as u64).checked_add_signed(offset),
+                    SeekFrom::Current(offset) => (*pos as u64).checked_add_signed(offset),
+                };
+
+                match new_pos {
+                    Some(new_pos) => {
+                        *pos = new_pos.min(content.len() as u64) as usize;
+
+                        Ok(())
+                    },
+                    None => Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "invalid seek to a negative or overflowing position",
+                    )),
+                }
+            },
+        }
+    }
+
+    fn poll_complete(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+    ) -> Poll<Result<u64, io::Error>> {
+        match &mut self.inner {
+            TempFileAsyncReaderInner::File {
+                async_file,
+            } => Pin::new(async_file).poll_complete(ctx),
+            TempFileAsyncReaderInner::Buffered {
+                pos, ..
+            } => Poll::Ready(Ok(*pos as u64)),
+        }
+    }
+}
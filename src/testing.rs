@@ -0,0 +1,38 @@
+//! Fixtures shared by the unit tests.
+
+use rocket::{
+    http::Header,
+    local::{asynchronous, blocking},
+    response::{Responder, Response},
+    Build, Rocket,
+};
+
+use crate::RawResponse;
+
+/// Create a local client of an empty Rocket instance.
+pub(crate) async fn client() -> asynchronous::Client {
+    client_of(rocket::build()).await
+}
+
+/// Create a local client of a Rocket instance, e.g. one with managed state.
+pub(crate) async fn client_of(rocket: Rocket<Build>) -> asynchronous::Client {
+    asynchronous::Client::untracked(rocket).await.unwrap()
+}
+
+/// Create a blocking local client of an empty Rocket instance, for tests which only need a request.
+pub(crate) fn blocking_client() -> blocking::Client {
+    blocking::Client::untracked(rocket::build()).unwrap()
+}
+
+/// Add headers to a request and respond to it.
+pub(crate) fn respond(
+    mut request: asynchronous::LocalRequest<'_>,
+    headers: &[(&'static str, &'static str)],
+    raw_response: RawResponse,
+) -> Response<'static> {
+    for (name, value) in headers {
+        request = request.header(Header::new(*name, *value));
+    }
+
+    raw_response.respond_to(request.inner()).unwrap()
+}
@@ -0,0 +1,158 @@
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use rocket::{
+    futures::future::BoxFuture,
+    tokio::{
+        self,
+        io::{AsyncRead, DuplexStream, ReadBuf},
+        task::JoinHandle,
+    },
+};
+
+/// The capacity of the pipe between a producer and the response.
+const PIPE_CAPACITY: usize = 64 * 1024;
+
+type Producer = Box<dyn FnOnce(DuplexStream) -> BoxFuture<'static, io::Result<()>> + Send>;
+
+/// Read what a producer writes to a pipe. The producer is spawned at the first read and aborted when the reader is dropped, and its error is returned as a read error after the data it has written.
+pub(crate) struct WriterAsyncReader {
+    producer: Option<Producer>,
+    reader:   Option<DuplexStream>,
+    task:     Option<JoinHandle<io::Result<()>>>,
+}
+
+impl WriterAsyncReader {
+    #[inline]
+    pub(crate) fn new<F, Fut>(producer: F) -> Self
+    where
+        F: FnOnce(DuplexStream) -> Fut + Send + 'static,
+        Fut: Future<Output = io::Result<()>> + Send + 'static, {
+        WriterAsyncReader {
+            producer: Some(Box::new(move |writer| Box::pin(producer(writer)))),
+            reader:   None,
+            task:     None,
+        }
+    }
+}
+
+impl AsyncRead for WriterAsyncReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), io::Error>> {
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        if let Some(producer) = self.producer.take() {
+            let (writer, reader) = tokio::io::duplex(PIPE_CAPACITY);
+
+            self.task = Some(tokio::spawn(producer(writer)));
+            self.reader = Some(reader);
+        }
+
+        if let Some(reader) = self.reader.as_mut() {
+            let filled = buf.filled().len();
+
+            ready!(Pin::new(reader).poll_read(ctx, buf))?;
+
+            if buf.filled().len() > filled {
+                return Poll::Ready(Ok(()));
+            }
+
+            self.reader = None;
+        }
+
+        if let Some(task) = self.task.as_mut() {
+            let result = ready!(Pin::new(task).poll(ctx));
+
+            self.task = None;
+
+            match result {
+                Ok(result) => result?,
+                Err(error) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, error))),
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Drop for WriterAsyncReader {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(task) = self.task.as_ref() {
+            task.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rocket::tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        sync::oneshot,
+        time,
+    };
+
+    use super::*;
+
+    #[rocket::async_test]
+    async fn read_until_producer_ends() {
+        let mut reader = WriterAsyncReader::new(|mut writer: DuplexStream| async move {
+            writer.write_all(b"hello").await
+        });
+
+        let mut data = Vec::new();
+
+        reader.read_to_end(&mut data).await.unwrap();
+
+        assert_eq!(b"hello", data.as_slice());
+    }
+
+    #[rocket::async_test]
+    async fn read_until_producer_fails() {
+        let mut reader = WriterAsyncReader::new(|mut writer: DuplexStream| async move {
+            writer.write_all(b"hello").await?;
+
+            Err(io::Error::new(io::ErrorKind::Other, "broken"))
+        });
+
+        let mut data = [0; 5];
+
+        reader.read_exact(&mut data).await.unwrap();
+
+        assert_eq!(b"hello", &data);
+        assert_eq!("broken", reader.read(&mut data).await.unwrap_err().to_string());
+    }
+
+    #[rocket::async_test]
+    async fn abort_producer_when_dropped() {
+        let (sender, receiver) = oneshot::channel::<()>();
+
+        let mut reader = WriterAsyncReader::new(|mut writer: DuplexStream| async move {
+            // the producer never ends, so the sender is only dropped when the producer is aborted
+            let _sender = sender;
+
+            writer.write_all(b"hello").await?;
+
+            std::future::pending().await
+        });
+
+        let mut data = [0; 5];
+
+        reader.read_exact(&mut data).await.unwrap();
+
+        drop(reader);
+
+        assert!(time::timeout(Duration::from_secs(5), receiver).await.unwrap().is_err());
+    }
+}